@@ -0,0 +1,123 @@
+use crate::circle::Circle;
+use crate::parallel::map_ranges;
+
+/// Finds the pairs of circles that touch, without testing every pair.
+///
+/// Implementations must report every overlapping pair whose layers, masks and
+/// groups let them collide, whatever the circles' sizes, each exactly once and
+/// in an order that depends only on the circles, not on how many threads
+/// shared the search.
+pub trait BroadPhase {
+    /// Name used by the `broadphase` terminal command.
+    fn name(&self) -> &'static str;
+
+    /// Rebuilds the structure for the circles' current positions.
+    fn update(&mut self, circles: &[Circle]);
+
+    /// Overlapping collidable pairs as of the last `update`, lower index first,
+    /// searched on up to `threads` threads.
+    fn pairs(&self, circles: &[Circle], threads: usize) -> Vec<(usize, usize)>;
+}
+
+/// Sorts circles along the x axis and only tests those whose extents overlap
+/// there. Suits scenes with a wide mix of sizes, where a grid's cell size
+/// cannot fit them all.
+#[derive(Clone, Debug, Default)]
+pub struct SweepAndPrune {
+    /// Circle indices ordered by the left edge of each circle.
+    order: Vec<usize>,
+}
+
+impl SweepAndPrune {
+    pub fn new() -> SweepAndPrune {
+        SweepAndPrune::default()
+    }
+}
+
+fn left(circle: &Circle) -> f64 {
+    circle.pinfo.pos.x - circle.radius
+}
+
+impl BroadPhase for SweepAndPrune {
+    fn name(&self) -> &'static str {
+        "sap"
+    }
+
+    fn update(&mut self, circles: &[Circle]) {
+        if self.order.len() != circles.len() {
+            self.order = (0..circles.len()).collect();
+        }
+        // Last step's order is nearly sorted already, which a stable sort handles quickly
+        self.order.sort_by(|a, b| left(&circles[*a]).total_cmp(&left(&circles[*b])).then(a.cmp(b)));
+    }
+
+    fn pairs(&self, circles: &[Circle], threads: usize) -> Vec<(usize, usize)> {
+        // Each thread sweeps from its own run of starting circles to the end
+        map_ranges(self.order.len(), threads, |starts| {
+            let mut pairs = Vec::new();
+            for k in starts {
+                let a = self.order[k];
+                let right = circles[a].pinfo.pos.x + circles[a].radius;
+                for &b in &self.order[k + 1..] {
+                    if left(&circles[b]) > right {
+                        break;
+                    }
+                    if circles[a].can_collide(&circles[b]) && circles[a].check_collision(&circles[b]) {
+                        pairs.push((a.min(b), a.max(b)));
+                    }
+                }
+            }
+            pairs
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::grid::Grid;
+    use crate::math::Double;
+    use crate::{CELL_SIZE, HEIGHT, WIDTH};
+
+    fn brute_force(circles: &[Circle]) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for a in 0..circles.len() {
+            for b in a + 1..circles.len() {
+                if circles[a].can_collide(&circles[b]) && circles[a].check_collision(&circles[b]) {
+                    pairs.push((a, b));
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn grid_and_sweep_match_brute_force_for_mixed_radii() {
+        let mut rng = ChaCha8Rng::seed_from_u64(22);
+        for _ in 0..50 {
+            let circles: Vec<Circle> = (0..80)
+                .map(|_| {
+                    let pos = Double { x: rng.gen_range(-100.0..WIDTH as f64 + 100.0), y: rng.gen_range(-100.0..HEIGHT as f64 + 100.0) };
+                    let radius = if rng.gen_bool(0.1) { rng.gen_range(50.0..150.0) } else { rng.gen_range(1.0..20.0) };
+                    Circle::new(pos, radius, [1.0; 4]).with_group(rng.gen_range(0..4))
+                })
+                .collect();
+            let expected = brute_force(&circles);
+
+            let mut broadphases: [Box<dyn BroadPhase>; 2] = [Box::new(Grid::new(WIDTH, HEIGHT, CELL_SIZE)), Box::new(SweepAndPrune::new())];
+            for broadphase in &mut broadphases {
+                broadphase.update(&circles);
+                let mut found = broadphase.pairs(&circles, 1);
+                assert!(found.iter().all(|(a, b)| a < b), "{} reported a pair higher index first", broadphase.name());
+                found.sort();
+                let len = found.len();
+                found.dedup();
+                assert_eq!(found.len(), len, "{} reported a pair twice", broadphase.name());
+                assert_eq!(found, expected, "{} disagrees with brute force", broadphase.name());
+            }
+        }
+    }
+}
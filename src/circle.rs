@@ -0,0 +1,383 @@
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use crate::math::Double;
+use crate::parameters::Parameters;
+use crate::{DEFAULT_DENSITY, HEIGHT, WIDTH};
+
+/// Wall impacts slower than this stop dead instead of bouncing, so resting circles do not jitter.
+const REST_SPEED: f64 = 1.0;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PhysicsInfo {
+    pub pos: Double,
+    pub vel: Double,
+    /// Acceleration evaluated at the end of the last step.
+    pub acc: Double,
+    /// Position at the start of the last fixed step, used to interpolate rendering.
+    pub prev_pos: Double,
+    pub angle: f64,
+    pub ang_vel: f64,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Circle {
+    pub radius: f64,
+    pub mass: f64,
+    pub pinfo: PhysicsInfo,
+    pub color: [f32; 4],
+    pub is_dragged: bool,
+    /// Pinned circles have infinite mass and stay where they are unless dragged.
+    pub pinned: bool,
+    /// Electric charge; like charges repel and opposite charges attract.
+    pub charge: f64,
+    /// Fluid particles feel SPH pressure and viscosity from each other.
+    pub fluid: bool,
+    /// Collision layers this circle belongs to, one per bit.
+    pub layers: u32,
+    /// Layers this circle collides with; a pair collides only if each is in the other's mask.
+    pub mask: u32,
+    /// Circles sharing a nonzero group never collide, such as the nodes of one rope.
+    pub group: u32,
+    /// Asleep circles are left out of integration and collision response until woken.
+    pub sleeping: bool,
+    /// Seconds the circle has been moving slower than `sleep_speed`.
+    pub sleep_time: f64,
+}
+
+impl Circle {
+    /// A circle at rest at `pos`, with its mass taken from `DEFAULT_DENSITY`.
+    pub fn new(pos: Double, radius: f64, color: [f32; 4]) -> Circle {
+        Circle {
+            radius,
+            mass: PI * radius * radius * DEFAULT_DENSITY,
+            pinfo: PhysicsInfo {
+                pos,
+                vel: Double { x: 0.0, y: 0.0 },
+                acc: Double { x: 0.0, y: 0.0 },
+                prev_pos: pos,
+                angle: 0.0,
+                ang_vel: 0.0,
+            },
+            color,
+            is_dragged: false,
+            pinned: false,
+            charge: 0.0,
+            fluid: false,
+            layers: 1,
+            mask: u32::MAX,
+            group: 0,
+            sleeping: false,
+            sleep_time: 0.0,
+        }
+    }
+
+    pub fn with_mass(mut self, mass: f64) -> Circle {
+        self.mass = mass;
+        self
+    }
+
+    /// Sets the mass to area × `density`.
+    pub fn with_density(mut self, density: f64) -> Circle {
+        self.mass = self.area() * density;
+        self
+    }
+
+    pub fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+
+    pub fn with_pinned(mut self, pinned: bool) -> Circle {
+        self.pinned = pinned;
+        self
+    }
+
+    pub fn with_charge(mut self, charge: f64) -> Circle {
+        self.charge = charge;
+        self
+    }
+
+    pub fn with_fluid(mut self, fluid: bool) -> Circle {
+        self.fluid = fluid;
+        self
+    }
+
+    pub fn with_layers(mut self, layers: u32, mask: u32) -> Circle {
+        self.layers = layers;
+        self.mask = mask;
+        self
+    }
+
+    pub fn with_group(mut self, group: u32) -> Circle {
+        self.group = group;
+        self
+    }
+
+    /// Wakes the circle and restarts its stillness timer.
+    pub fn wake(&mut self) {
+        self.sleeping = false;
+        self.sleep_time = 0.0;
+    }
+
+    /// Whether the layers, masks and groups of the two circles let them collide.
+    pub fn can_collide(&self, other: &Circle) -> bool {
+        if self.group != 0 && self.group == other.group {
+            return false;
+        }
+        self.layers & other.mask != 0 && other.layers & self.mask != 0
+    }
+
+    /// Zero for circles that cannot be pushed, such as pinned or dragged ones.
+    pub fn inv_mass(&self) -> f64 {
+        if self.is_dragged || self.pinned || self.mass <= 0.0 {
+            0.0
+        } else {
+            1.0 / self.mass
+        }
+    }
+
+    /// Inverse moment of inertia of a solid disc, zero when `inv_mass` is.
+    pub fn inv_inertia(&self) -> f64 {
+        let inv_mass = self.inv_mass();
+        if inv_mass == 0.0 || self.radius <= 0.0 {
+            0.0
+        } else {
+            2.0 * inv_mass / (self.radius * self.radius)
+        }
+    }
+
+    /// Velocity of the point at offset `r` from the centre, including spin.
+    pub fn point_velocity(&self, r: Double) -> Double {
+        self.pinfo.vel + r.perp() * self.pinfo.ang_vel
+    }
+
+    pub fn apply_impulse(&mut self, impulse: Double, r: Double) {
+        self.pinfo.vel = self.pinfo.vel + impulse * self.inv_mass();
+        self.pinfo.ang_vel += r.cross(impulse) * self.inv_inertia();
+    }
+
+    /// Applies Coulomb friction against a wall whose `normal` points away from it,
+    /// given the normal impulse the wall just delivered.
+    fn apply_wall_friction(&mut self, normal: Double, normal_impulse: f64, params: &Parameters) {
+        let r = normal * -self.radius;
+        let tangent = normal.perp();
+        let slip = self.point_velocity(r).dot(tangent);
+        let k = self.inv_mass() + r.cross(tangent).powi(2) * self.inv_inertia();
+        if k == 0.0 {
+            return;
+        }
+        let jt = friction_impulse(slip, k, normal_impulse, params);
+        self.apply_impulse(tangent * jt, r);
+    }
+
+    /// Pushes the circle back inside the window, bouncing off any wall it
+    /// moved into and applying friction along it.
+    pub fn collide_walls(&mut self, params: &Parameters) {
+        if self.pinned && !self.is_dragged {
+            return;
+        }
+        let walls = [
+            (Double { x: 1.0, y: 0.0 }, self.radius - self.pinfo.pos.x),
+            (Double { x: -1.0, y: 0.0 }, self.pinfo.pos.x + self.radius - WIDTH as f64),
+            (Double { x: 0.0, y: 1.0 }, self.radius - self.pinfo.pos.y),
+            (Double { x: 0.0, y: -1.0 }, self.pinfo.pos.y + self.radius - HEIGHT as f64),
+        ];
+        for (normal, depth) in walls {
+            if depth <= 0.0 {
+                continue;
+            }
+            self.pinfo.pos = self.pinfo.pos + normal * depth;
+
+            let normal_vel = self.pinfo.vel.dot(normal);
+            if normal_vel >= 0.0 {
+                continue;
+            }
+            let bounced_vel = if -normal_vel < REST_SPEED { 0.0 } else { -normal_vel * (1.0 - params.collide_loss) };
+            self.pinfo.vel = self.pinfo.vel + normal * (bounced_vel - normal_vel);
+            self.apply_wall_friction(normal, (bounced_vel - normal_vel) * self.mass, params);
+        }
+    }
+
+    /// Fuses `other` into this circle, conserving mass, area, momentum and spin.
+    pub fn absorb(&mut self, other: &Circle) {
+        let mass = self.mass + other.mass;
+        if mass <= 0.0 {
+            return;
+        }
+        let (w1, w2) = (self.mass / mass, other.mass / mass);
+        let inertia = |circle: &Circle| 0.5 * circle.mass * circle.radius * circle.radius;
+        let spin = inertia(self) * self.pinfo.ang_vel + inertia(other) * other.pinfo.ang_vel;
+
+        self.pinfo.pos = self.pinfo.pos * w1 + other.pinfo.pos * w2;
+        self.pinfo.prev_pos = self.pinfo.prev_pos * w1 + other.pinfo.prev_pos * w2;
+        self.pinfo.vel = self.pinfo.vel * w1 + other.pinfo.vel * w2;
+        for (channel, other_channel) in self.color.iter_mut().zip(other.color) {
+            *channel = *channel * w1 as f32 + other_channel * w2 as f32;
+        }
+        self.radius = (self.radius * self.radius + other.radius * other.radius).sqrt();
+        self.mass = mass;
+        self.charge += other.charge;
+        self.pinfo.ang_vel = spin / inertia(self);
+    }
+
+    /// Render position `alpha` of the way from the previous step to the current one.
+    pub fn interpolated_pos(&self, alpha: f64) -> Double {
+        self.pinfo.prev_pos + (self.pinfo.pos - self.pinfo.prev_pos) * alpha
+    }
+
+    pub fn find_grid_pos(&self, cell_size: i32) -> (i32, i32) {
+        let x = (self.pinfo.pos.x / cell_size as f64).floor() as i32;
+        let y = (self.pinfo.pos.y / cell_size as f64).floor() as i32;
+        (x, y)
+    }
+
+    pub fn check_collision(&self, other: &Circle) -> bool {
+        let dx = self.pinfo.pos.x - other.pinfo.pos.x;
+        let dy = self.pinfo.pos.y - other.pinfo.pos.y;
+        let distance = (dx * dx + dy * dy).sqrt();
+        distance <= self.radius + other.radius
+    }
+
+    pub fn contains(&self, point: Double) -> bool {
+        self.pinfo.pos.dist(point) <= self.radius
+    }
+}
+
+/// Tangential impulse that cancels `slip` if static friction can hold it,
+/// otherwise a kinetic friction impulse opposing the slip.
+pub(crate) fn friction_impulse(slip: f64, k: f64, normal_impulse: f64, params: &Parameters) -> f64 {
+    let jt = -slip / k;
+    if jt.abs() <= params.static_friction * normal_impulse {
+        jt
+    } else {
+        -slip.signum() * params.kinetic_friction * normal_impulse
+    }
+}
+
+/// A touching pair of circles, as found before the collision was resolved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+    /// Circle indices, lower first.
+    pub a: usize,
+    pub b: usize,
+    /// Unit vector from `b` towards `a`; zero when their centres coincide.
+    pub normal: Double,
+    /// How far the circles overlap.
+    pub depth: f64,
+    /// Velocity of `a`'s contact point relative to `b`'s; negative along the
+    /// normal while they approach.
+    pub relative_velocity: Double,
+}
+
+impl Contact {
+    pub fn between(circles: &[Circle], a: usize, b: usize) -> Contact {
+        let delta = circles[a].pinfo.pos - circles[b].pinfo.pos;
+        let distance = delta.magnitude();
+        let normal = if distance > 0.0 { delta * (1.0 / distance) } else { Double { x: 0.0, y: 0.0 } };
+        let r1 = normal * -circles[a].radius;
+        let r2 = normal * circles[b].radius;
+        Contact {
+            a,
+            b,
+            normal,
+            depth: circles[a].radius + circles[b].radius - distance,
+            relative_velocity: circles[a].point_velocity(r1) - circles[b].point_velocity(r2),
+        }
+    }
+
+    /// Speed at which the circles were closing along the normal.
+    pub fn approach_speed(&self) -> f64 {
+        (-self.relative_velocity.dot(self.normal)).max(0.0)
+    }
+}
+
+/// Fraction of a step, from 0 to 1, at which two circles moving in straight
+/// lines from `start1` to `end1` and from `start2` to `end2` first come within
+/// `reach` of each other, or `None` if they never do or already were.
+pub(crate) fn time_of_impact(start1: Double, end1: Double, start2: Double, end2: Double, reach: f64) -> Option<f64> {
+    let offset = start1 - start2;
+    let motion = (end1 - start1) - (end2 - start2);
+    let a = motion.dot(motion);
+    let b = 2.0 * offset.dot(motion);
+    let c = offset.dot(offset) - reach * reach;
+    if c <= 0.0 || a == 0.0 {
+        return None;
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / (2.0 * a);
+    (0.0..=1.0).contains(&t).then_some(t)
+}
+
+/// Whether two circles sit too close together to find a collision normal.
+pub(crate) fn coincident(circles: &[Circle], i1: usize, i2: usize) -> bool {
+    circles[i1].pinfo.pos.dist(circles[i2].pinfo.pos) < 1.0
+}
+
+/// Separates two overlapping circles in proportion to their inverse masses and
+/// applies the restitution and friction impulses at the contact point. Exactly
+/// coincident circles are jittered apart at random instead, leaving pinned and
+/// dragged circles where they are.
+pub(crate) fn resolve_collision<R: Rng>(circles: &mut [Circle], i1: usize, i2: usize, params: &Parameters, rng: &mut R) {
+    if coincident(circles, i1, i2) {
+        for obj in [i1, i2] {
+            if circles[obj].inv_mass() == 0.0 {
+                continue;
+            }
+            circles[obj].pinfo.pos.x += rng.gen::<f64>() * 2.0 - 1.0;
+            circles[obj].pinfo.pos.y += rng.gen::<f64>() * 2.0 - 1.0;
+        }
+    } else {
+        resolve_contact(circles, i1, i2, params);
+    }
+}
+
+/// The deterministic part of [`resolve_collision`], for circles at least one
+/// unit apart.
+pub(crate) fn resolve_contact(circles: &mut [Circle], i1: usize, i2: usize, params: &Parameters) {
+    let dx = circles[i1].pinfo.pos.x - circles[i2].pinfo.pos.x;
+    let dy = circles[i1].pinfo.pos.y - circles[i2].pinfo.pos.y;
+    let distance = (dx * dx + dy * dy).sqrt();
+
+    let w1 = circles[i1].inv_mass();
+    let w2 = circles[i2].inv_mass();
+    let w_sum = w1 + w2;
+    if w_sum == 0.0 {
+        return;
+    }
+
+    // Normal points from circle 2 towards circle 1
+    let normal = Double { x: dx / distance, y: dy / distance };
+    let overlap = circles[i1].radius + circles[i2].radius - distance;
+
+    // Push apart in proportion to inverse mass so the heavier circle moves less
+    circles[i1].pinfo.pos = circles[i1].pinfo.pos + normal * (overlap * w1 / w_sum);
+    circles[i2].pinfo.pos = circles[i2].pinfo.pos - normal * (overlap * w2 / w_sum);
+
+    // Contact point offsets from each centre
+    let r1 = normal * -circles[i1].radius;
+    let r2 = normal * circles[i2].radius;
+
+    let rel_vel = circles[i1].point_velocity(r1) - circles[i2].point_velocity(r2);
+    let approach = rel_vel.dot(normal);
+    if approach >= 0.0 {
+        return;
+    }
+
+    let jn = -(1.0 + params.restitution) * approach / w_sum;
+    circles[i1].apply_impulse(normal * jn, r1);
+    circles[i2].apply_impulse(normal * -jn, r2);
+
+    let tangent = normal.perp();
+    let rel_vel = circles[i1].point_velocity(r1) - circles[i2].point_velocity(r2);
+    let slip = rel_vel.dot(tangent);
+    let k = w_sum
+        + r1.cross(tangent).powi(2) * circles[i1].inv_inertia()
+        + r2.cross(tangent).powi(2) * circles[i2].inv_inertia();
+    let jt = friction_impulse(slip, k, jn, params);
+    circles[i1].apply_impulse(tangent * jt, r1);
+    circles[i2].apply_impulse(tangent * -jt, r2);
+}
@@ -0,0 +1,180 @@
+use crate::grid::Grid;
+use crate::links::spring_force;
+use crate::math::Double;
+use crate::nbody::{barnes_hut_accelerations, direct_accelerations};
+use crate::parallel::{for_each_mut, scatter_add};
+use crate::sleep::asleep;
+use crate::sph::sph_accelerations;
+use crate::world::World;
+use crate::{CELL_SIZE, HEIGHT, WIDTH};
+
+impl World {
+    /// Acceleration of every circle for an arbitrary system state, so the
+    /// integrators can evaluate forces at intermediate positions.
+    pub(crate) fn accelerations(&self, pos: &[Double], vel: &[Double]) -> Vec<Double> {
+        let params = &self.params;
+        let mut acc = vec![Double { x: 0.0, y: 0.0 }; vel.len()];
+        let circles = &self.circles;
+        for_each_mut(&mut acc, params.threads, |i, acc| {
+            if !circles[i].sleeping {
+                *acc = params.gravity - vel[i] * params.air_resistance;
+            }
+        });
+
+        scatter_add(&mut acc, &self.links, params.threads, |link, forces| {
+            if asleep(circles, &[link.c1, link.c2]) {
+                return;
+            }
+            if let Some(force) = spring_force(pos, vel, link, params) {
+                forces.push((link.c1, force));
+                forces.push((link.c2, force * -1.0));
+            }
+        });
+
+        scatter_add(&mut acc, &self.pressurebodies, params.threads, |body, pushes| {
+            if !asleep(circles, &body.nodes) {
+                body.pushes(pos, pushes);
+            }
+        });
+
+        let fluid: Vec<usize> = (0..self.circles.len()).filter(|id| self.circles[*id].fluid).collect();
+        if !fluid.is_empty() {
+            let mass: Vec<f64> = self.circles.iter().map(|circle| circle.mass).collect();
+            sph_accelerations(pos, vel, &mass, &fluid, params, &mut acc);
+        }
+
+        let zones = &self.zones;
+        if !zones.is_empty() {
+            for_each_mut(&mut acc, params.threads, |i, acc| {
+                if circles[i].sleeping {
+                    return;
+                }
+                for zone in zones {
+                    *acc = *acc + zone.acceleration(pos[i], vel[i]);
+                }
+            });
+        }
+
+        if params.nbody {
+            let mass: Vec<f64> = self.circles.iter().map(|circle| circle.mass).collect();
+            if params.barnes_hut {
+                barnes_hut_accelerations(pos, &mass, params.grav_const, params.softening, params.theta, params.threads, &mut acc);
+            } else {
+                direct_accelerations(pos, &mass, params.grav_const, params.softening, params.threads, &mut acc);
+            }
+        }
+
+        scatter_add(&mut acc, &self.charged_pairs(pos), params.threads, |&(i, j), forces| {
+            let (c1, c2) = (&circles[i], &circles[j]);
+            let delta = pos[i] - pos[j];
+            let dist_sq = delta.dot(delta) + params.softening * params.softening;
+            if dist_sq == 0.0 {
+                return;
+            }
+            // Positive for like charges, pushing circle 1 away from circle 2
+            let force = delta * (params.coulomb_const * c1.charge * c2.charge / (dist_sq * dist_sq.sqrt()));
+            if c1.mass > 0.0 {
+                forces.push((i, force * (1.0 / c1.mass)));
+            }
+            if c2.mass > 0.0 {
+                forces.push((j, force * (-1.0 / c2.mass)));
+            }
+        });
+
+        // Dragged circles follow the cursor and ignore forces, sleeping ones feel none
+        for (acc, circle) in acc.iter_mut().zip(&self.circles) {
+            if circle.inv_mass() == 0.0 || circle.sleeping {
+                *acc = Double { x: 0.0, y: 0.0 };
+            }
+        }
+        acc
+    }
+
+    /// Pairs of charged circles within the Coulomb cutoff at positions `pos`.
+    fn charged_pairs(&self, pos: &[Double]) -> Vec<(usize, usize)> {
+        let params = &self.params;
+        if params.coulomb_const == 0.0 || params.coulomb_cutoff <= 0.0 {
+            return Vec::new();
+        }
+        let mut grid = Grid::new(WIDTH, HEIGHT, CELL_SIZE);
+        let mut any = false;
+        for (id, circle) in self.circles.iter().enumerate() {
+            if circle.charge != 0.0 {
+                grid.add_point(pos[id], id);
+                any = true;
+            }
+        }
+        if !any {
+            return Vec::new();
+        }
+        grid.pairs_within(pos, params.coulomb_cutoff, params.threads)
+    }
+
+    pub fn kinetic_energy(&self) -> f64 {
+        self.circles
+            .iter()
+            .map(|circle| {
+                let inertia = 0.5 * circle.mass * circle.radius * circle.radius;
+                0.5 * circle.mass * circle.pinfo.vel.dot(circle.pinfo.vel) + 0.5 * inertia * circle.pinfo.ang_vel * circle.pinfo.ang_vel
+            })
+            .sum()
+    }
+
+    /// Gravitational energy measured from the bottom-left corner, mutual
+    /// gravitational energy in n-body mode, electric energy of charged pairs
+    /// within the cutoff, plus the energy stored in springs and pressure
+    /// bodies. Springs and gas act per unit mass, so their energy is weighted
+    /// by the mean mass of the circles they push.
+    pub fn potential_energy(&self) -> f64 {
+        let params = &self.params;
+        let gravity: f64 = self
+            .circles
+            .iter()
+            .map(|circle| circle.mass * params.gravity.dot(Double { x: 0.0, y: HEIGHT as f64 } - circle.pinfo.pos))
+            .sum();
+        let springs: f64 = self
+            .links
+            .iter()
+            .map(|link| {
+                let c1 = &self.circles[link.c1];
+                let c2 = &self.circles[link.c2];
+                let stretch = c1.pinfo.pos.dist(c2.pinfo.pos) - link.rest_length;
+                0.5 * params.spring_const * stretch * stretch * 0.5 * (c1.mass + c2.mass)
+            })
+            .sum();
+        let positions: Vec<Double> = self.circles.iter().map(|circle| circle.pinfo.pos).collect();
+        let gas: f64 = self
+            .pressurebodies
+            .iter()
+            .filter(|body| !body.nodes.is_empty())
+            .map(|body| {
+                let mean_mass = body.nodes.iter().map(|node| self.circles[*node].mass).sum::<f64>() / body.nodes.len() as f64;
+                -body.gas_amount * body.signed_area(&positions).abs().max(1.0).ln() * mean_mass
+            })
+            .sum();
+        let mut nbody = 0.0;
+        if params.nbody {
+            for (i, c1) in self.circles.iter().enumerate() {
+                for c2 in &self.circles[i + 1..] {
+                    let dist_sq = c1.pinfo.pos.dist(c2.pinfo.pos).powi(2) + params.softening * params.softening;
+                    if dist_sq > 0.0 {
+                        nbody -= params.grav_const * c1.mass * c2.mass / dist_sq.sqrt();
+                    }
+                }
+            }
+        }
+        let electric: f64 = self
+            .charged_pairs(&positions)
+            .into_iter()
+            .map(|(i, j)| {
+                let dist_sq = positions[i].dist(positions[j]).powi(2) + params.softening * params.softening;
+                params.coulomb_const * self.circles[i].charge * self.circles[j].charge / dist_sq.sqrt()
+            })
+            .sum();
+        gravity + springs + gas + nbody + electric
+    }
+
+    pub fn total_energy(&self) -> f64 {
+        self.kinetic_energy() + self.potential_energy()
+    }
+}
@@ -0,0 +1,119 @@
+use std::f64::consts::PI;
+
+use crate::circle::Circle;
+use crate::links::{AngleLink, Link, StaticLink};
+use crate::math::Double;
+
+pub fn create_rope(circles: &mut Vec<Circle>, staticlinks: &mut Vec<StaticLink>, anchor_pos: Double, rope_length: f64, segment_num: i64, pin_first: bool) {
+    let segmental_node_radius = 1.0;
+    for i in 0..segment_num+2 {
+        let pos = Double {
+            x: anchor_pos.x + (i as f64 * rope_length / segment_num as f64) * 0.0,
+            y: anchor_pos.y + (i as f64 * rope_length / segment_num as f64) * 1.0,
+        };
+        circles.push(Circle::new(pos, segmental_node_radius, [0.0, 0.0, 0.0, 0.0]).with_pinned(pin_first && i == 0));
+        if i > 0 {
+            staticlinks.push(StaticLink::new(circles.len() - 2, circles.len() - 1, rope_length / segment_num as f64));
+        }
+    }
+}
+
+/// A `width` × `height` lattice of small circles hanging from `top_left`,
+/// `spacing` apart. Neighbours are joined by structural links, each square
+/// is braced by two shear links and every second node by a bend link.
+pub fn create_cloth(circles: &mut Vec<Circle>, staticlinks: &mut Vec<StaticLink>, top_left: Double, width: usize, height: usize, spacing: f64, pin_top: bool) {
+    let first = circles.len();
+    for y in 0..height {
+        for x in 0..width {
+            let pos = top_left + Double { x: x as f64 * spacing, y: y as f64 * spacing };
+            circles.push(Circle::new(pos, 1.0, [0.0, 0.0, 0.0, 0.0]).with_pinned(pin_top && y == 0));
+        }
+    }
+
+    let node = |x: usize, y: usize| first + y * width + x;
+    let mut join = |a: usize, b: usize| {
+        let rest_length = circles[a].pinfo.pos.dist(circles[b].pinfo.pos);
+        staticlinks.push(StaticLink::new(a, b, rest_length));
+    };
+    for y in 0..height {
+        for x in 0..width {
+            if x + 1 < width {
+                join(node(x, y), node(x + 1, y));
+            }
+            if y + 1 < height {
+                join(node(x, y), node(x, y + 1));
+            }
+            if x + 1 < width && y + 1 < height {
+                join(node(x, y), node(x + 1, y + 1));
+                join(node(x + 1, y), node(x, y + 1));
+            }
+            if x + 2 < width {
+                join(node(x, y), node(x + 2, y));
+            }
+            if y + 2 < height {
+                join(node(x, y), node(x, y + 2));
+            }
+        }
+    }
+}
+
+pub fn create_softbody(circles: &mut Vec<Circle>, links: &mut Vec<StaticLink>, num_of_circles: usize, radius: f64, sub_radius: f64, pos: Double, color: [f32; 4]) {
+    let circum = 2.0 * PI * radius;
+    let rest_len = circum / num_of_circles as f64;
+    let circles_len = circles.len();
+    for i in 0..num_of_circles {
+        let node_pos = Double {
+            x: radius * (i as f64 * 2.0 * PI / num_of_circles as f64).cos() + pos.x,
+            y: radius * (i as f64 * 2.0 * PI / num_of_circles as f64).sin() + pos.y,
+        };
+        circles.push(Circle::new(node_pos, sub_radius, color));
+        if i > 0 {
+            links.push(StaticLink::new(i + circles_len - 1, i + circles_len, rest_len));
+        }
+    }
+    links.push(StaticLink::new(circles_len, circles_len + num_of_circles - 1, rest_len));
+}
+
+pub fn create_spring_softbody(circles: &mut Vec<Circle>, links: &mut Vec<Link>, num_of_circles: usize, radius: f64, sub_radius: f64, pos: Double, color: [f32; 4]) {
+    let circum = 2.0 * PI * radius;
+    let rest_len = circum / num_of_circles as f64;
+    let circles_len = circles.len();
+    for i in 0..num_of_circles {
+        let node_pos = Double {
+            x: radius * (i as f64 * 2.0 * PI / num_of_circles as f64).cos() + pos.x,
+            y: radius * (i as f64 * 2.0 * PI / num_of_circles as f64).sin() + pos.y,
+        };
+        circles.push(Circle::new(node_pos, sub_radius, color));
+        if i > 0 {
+            links.push(Link::new(i + circles_len - 1, i + circles_len, rest_len));
+        }
+    }
+    links.push(Link::new(circles_len, circles_len + num_of_circles - 1, rest_len));
+}
+
+/// A ring of unconnected circles, for bodies held together by something other than links.
+pub fn create_ring(circles: &mut Vec<Circle>, num_of_circles: usize, radius: f64, sub_radius: f64, pos: Double, color: [f32; 4]) {
+    for i in 0..num_of_circles {
+        let node_pos = Double {
+            x: radius * (i as f64 * 2.0 * PI / num_of_circles as f64).cos() + pos.x,
+            y: radius * (i as f64 * 2.0 * PI / num_of_circles as f64).sin() + pos.y,
+        };
+        circles.push(Circle::new(node_pos, sub_radius, color));
+    }
+}
+
+/// Adds an angle link at every interior node of the chain formed by the
+/// circles from index `first` onwards, holding the shape they have now. A
+/// `closed` chain wraps round so its first and last nodes bend as well.
+pub fn create_bending(circles: &[Circle], anglelinks: &mut Vec<AngleLink>, first: usize, closed: bool, stiffness: f64) {
+    let num = circles.len() - first;
+    if num < 3 {
+        return;
+    }
+    let joints = if closed { 0..num } else { 1..num - 1 };
+    for i in joints {
+        let prev = first + (i + num - 1) % num;
+        let next = first + (i + 1) % num;
+        anglelinks.push(AngleLink::at_current_angle(circles, prev, first + i, next, stiffness));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::broadphase::BroadPhase;
+use crate::circle::Circle;
+use crate::math::Double;
+use crate::parallel::map_ranges;
+
+#[derive(Clone, Debug)]
+struct Cell {
+    objects: Vec<i64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Grid {
+    cell_size: i32,
+    cells: Vec<Vec<Cell>>,
+    num_cells_x: i32,
+    num_cells_y: i32,
+}
+
+impl Grid {
+    pub fn new(width: i32, height: i32, cell_size: i32) -> Grid {
+        let num_cells_x = (width as f64 / cell_size as f64).ceil() as i32;
+        let num_cells_y = (height as f64 / cell_size as f64).ceil() as i32;
+        let cells = vec![vec![Cell { objects: Vec::new() }; num_cells_y as usize]; num_cells_x as usize];
+        Grid {
+            cell_size,
+            cells,
+            num_cells_x,
+            num_cells_y,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        for x in 0..self.num_cells_x {
+            for y in 0..self.num_cells_y {
+                self.cells[x as usize][y as usize].objects.clear();
+            }
+        }
+    }
+
+    /// Adds a circle to every cell its bounding box touches, so circles larger
+    /// than a cell still meet everything they overlap.
+    pub fn add_obj(&mut self, obj: Circle, obj_id: i64) {
+        let reach = Double { x: obj.radius, y: obj.radius };
+        let (min_x, min_y) = self.clamped_cell(obj.pinfo.pos - reach);
+        let (max_x, max_y) = self.clamped_cell(obj.pinfo.pos + reach);
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
+                self.cells[x as usize][y as usize].objects.push(obj_id);
+            }
+        }
+    }
+
+    /// Buckets a bare point, clamping it into the nearest edge cell when it lies outside the grid.
+    pub fn add_point(&mut self, pos: Double, id: usize) {
+        let (x, y) = self.clamped_cell(pos);
+        self.cells[x as usize][y as usize].objects.push(id as i64);
+    }
+
+    fn clamped_cell(&self, pos: Double) -> (i32, i32) {
+        let x = ((pos.x / self.cell_size as f64).floor() as i32).clamp(0, self.num_cells_x - 1);
+        let y = ((pos.y / self.cell_size as f64).floor() as i32).clamp(0, self.num_cells_y - 1);
+        (x, y)
+    }
+
+    /// Every object in every cell as `(x, y, k)`, meaning the `k`th object of
+    /// cell `(x, y)`, column by column. Splitting the searches over these
+    /// rather than over columns gives each thread a share of the circles.
+    fn entries(&self) -> Vec<(i32, i32, usize)> {
+        let mut entries = Vec::new();
+        for x in 0..self.num_cells_x {
+            for y in 0..self.num_cells_y {
+                entries.extend((0..self.cells[x as usize][y as usize].objects.len()).map(|k| (x, y, k)));
+            }
+        }
+        entries
+    }
+
+    /// Every pair of points added with `add_point` that lie closer than
+    /// `cutoff`, each reported once with the lower index first. The points are
+    /// shared out between up to `threads` threads.
+    pub fn pairs_within(&self, pos: &[Double], cutoff: f64, threads: usize) -> Vec<(usize, usize)> {
+        let entries = self.entries();
+        map_ranges(entries.len(), threads, |range| self.pairs_within_entries(pos, cutoff, &entries[range]))
+    }
+
+    fn pairs_within_entries(&self, pos: &[Double], cutoff: f64, entries: &[(i32, i32, usize)]) -> Vec<(usize, usize)> {
+        let reach = (cutoff / self.cell_size as f64).ceil() as i32;
+        let mut pairs: Vec<(usize, usize)> = Vec::new();
+
+        for &(x, y, k) in entries {
+            let id = self.cells[x as usize][y as usize].objects[k] as usize;
+            for i in (x - reach).max(0)..(x + reach + 1).min(self.num_cells_x) {
+                for j in (y - reach).max(0)..(y + reach + 1).min(self.num_cells_y) {
+                    for &other_id in &self.cells[i as usize][j as usize].objects {
+                        let other_id = other_id as usize;
+                        if id < other_id && pos[id].dist(pos[other_id]) < cutoff {
+                            pairs.push((id, other_id));
+                        }
+                    }
+                }
+            }
+        }
+
+        pairs
+    }
+
+    /// Every pair of overlapping circles added with `add_obj` whose layers,
+    /// masks and groups let them collide, each reported once with the lower
+    /// index first. The circles are shared out between up to `threads`
+    /// threads.
+    pub fn find_collisions(&self, circles: &[Circle], threads: usize) -> Vec<(usize, usize)> {
+        let entries = self.entries();
+        map_ranges(entries.len(), threads, |range| self.find_collisions_in_entries(circles, &entries[range]))
+    }
+
+    fn find_collisions_in_entries(&self, circles: &[Circle], entries: &[(i32, i32, usize)]) -> Vec<(usize, usize)> {
+        let mut collisions: Vec<(usize, usize)> = Vec::new();
+
+        for &(x, y, k) in entries {
+            let objects = &self.cells[x as usize][y as usize].objects;
+            let obj_id = &objects[k];
+            for other_obj_id in &objects[k + 1..] {
+                let (a, b) = (*obj_id.min(other_obj_id) as usize, *obj_id.max(other_obj_id) as usize);
+                let (obj, other_obj) = (&circles[a], &circles[b]);
+
+                // Circles spanning several cells share more than one; only
+                // the cell holding the corner of their overlap reports them
+                let corner = Double {
+                    x: (obj.pinfo.pos.x - obj.radius).max(other_obj.pinfo.pos.x - other_obj.radius),
+                    y: (obj.pinfo.pos.y - obj.radius).max(other_obj.pinfo.pos.y - other_obj.radius),
+                };
+                if self.clamped_cell(corner) != (x, y) {
+                    continue;
+                }
+                if obj.can_collide(other_obj) && obj.check_collision(other_obj) {
+                    collisions.push((a, b));
+                }
+            }
+        }
+
+        collisions
+    }
+}
+
+impl BroadPhase for Grid {
+    fn name(&self) -> &'static str {
+        "grid"
+    }
+
+    fn update(&mut self, circles: &[Circle]) {
+        self.reset();
+        for (i, circle) in circles.iter().enumerate() {
+            self.add_obj(*circle, i as i64);
+        }
+    }
+
+    fn pairs(&self, circles: &[Circle], threads: usize) -> Vec<(usize, usize)> {
+        self.find_collisions(circles, threads)
+    }
+}
@@ -0,0 +1,111 @@
+use crate::math::Double;
+use crate::parallel::{for_each_mut, map_ranges};
+
+/// Numerical scheme used to advance positions and velocities each step.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Integrator {
+    #[default]
+    SemiImplicitEuler,
+    VelocityVerlet,
+    Rk4,
+}
+
+impl Integrator {
+    pub const ALL: [Integrator; 3] = [Integrator::SemiImplicitEuler, Integrator::VelocityVerlet, Integrator::Rk4];
+
+    /// Name used by the `integrator` terminal command.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Integrator::SemiImplicitEuler => "euler",
+            Integrator::VelocityVerlet => "verlet",
+            Integrator::Rk4 => "rk4",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Integrator> {
+        Integrator::ALL.into_iter().find(|integrator| integrator.name() == name)
+    }
+
+    /// Advances `pos` and `vel` by `dt`, where `accel` gives the acceleration of
+    /// every body for a full system state, and returns the acceleration at the
+    /// end of the step. Only bodies marked in `moving` are advanced; the rest
+    /// keep their state untouched. Each body is updated on its own, so
+    /// spreading them over `threads` does not change the result.
+    pub fn integrate<F>(&self, pos: &mut [Double], vel: &mut [Double], dt: f64, moving: &[bool], threads: usize, accel: F) -> Vec<Double>
+    where
+        F: Fn(&[Double], &[Double]) -> Vec<Double>,
+    {
+        match self {
+            Integrator::SemiImplicitEuler => {
+                let a = accel(pos, vel);
+                for_each_mut(vel, threads, |i, vel| {
+                    if moving[i] {
+                        *vel = *vel + a[i] * dt;
+                    }
+                });
+                for_each_mut(pos, threads, |i, pos| {
+                    if moving[i] {
+                        *pos = *pos + vel[i] * dt;
+                    }
+                });
+                a
+            }
+            Integrator::VelocityVerlet => {
+                let a0 = accel(pos, vel);
+                for_each_mut(pos, threads, |i, pos| {
+                    if moving[i] {
+                        *pos = *pos + vel[i] * dt + a0[i] * (0.5 * dt * dt);
+                    }
+                });
+                for_each_mut(vel, threads, |i, vel| {
+                    if moving[i] {
+                        *vel = *vel + a0[i] * (0.5 * dt);
+                    }
+                });
+                // Velocity dependent forces see the half-step velocity
+                let a1 = accel(pos, vel);
+                for_each_mut(vel, threads, |i, vel| {
+                    if moving[i] {
+                        *vel = *vel + a1[i] * (0.5 * dt);
+                    }
+                });
+                a1
+            }
+            Integrator::Rk4 => {
+                let offset = |base: &[Double], delta: &[Double], h: f64| -> Vec<Double> {
+                    map_ranges(base.len(), threads, |range| range.map(|i| if moving[i] { base[i] + delta[i] * h } else { base[i] }).collect())
+                };
+
+                let k1_v = accel(pos, vel);
+                let k1_x = vel.to_vec();
+
+                let x2 = offset(pos, &k1_x, 0.5 * dt);
+                let v2 = offset(vel, &k1_v, 0.5 * dt);
+                let k2_v = accel(&x2, &v2);
+                let k2_x = v2;
+
+                let x3 = offset(pos, &k2_x, 0.5 * dt);
+                let v3 = offset(vel, &k2_v, 0.5 * dt);
+                let k3_v = accel(&x3, &v3);
+                let k3_x = v3;
+
+                let x4 = offset(pos, &k3_x, dt);
+                let v4 = offset(vel, &k3_v, dt);
+                let k4_v = accel(&x4, &v4);
+                let k4_x = v4;
+
+                for_each_mut(pos, threads, |i, pos| {
+                    if moving[i] {
+                        *pos = *pos + (k1_x[i] + k2_x[i] * 2.0 + k3_x[i] * 2.0 + k4_x[i]) * (dt / 6.0);
+                    }
+                });
+                for_each_mut(vel, threads, |i, vel| {
+                    if moving[i] {
+                        *vel = *vel + (k1_v[i] + k2_v[i] * 2.0 + k3_v[i] * 2.0 + k4_v[i]) * (dt / 6.0);
+                    }
+                });
+                k4_v
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Headless physics core of RustyPhysics.
+//!
+//! Everything needed to run a simulation without opening a window lives here;
+//! the `RustyPhysics` binary is only a piston front end on top of [`World`].
+
+pub mod broadphase;
+pub mod circle;
+pub mod forces;
+pub mod generators;
+pub mod grid;
+pub mod integrator;
+pub mod links;
+pub mod math;
+pub mod nbody;
+mod parallel;
+pub mod parameters;
+pub mod pressure;
+pub mod shapematch;
+pub mod sleep;
+pub mod sph;
+pub mod timestep;
+pub mod world;
+pub mod zones;
+
+pub use broadphase::{BroadPhase, SweepAndPrune};
+pub use circle::{Circle, Contact, PhysicsInfo};
+pub use grid::Grid;
+pub use integrator::Integrator;
+pub use links::{AngleLink, BrokenLink, Link, StaticLink};
+pub use math::Double;
+pub use parameters::Parameters;
+pub use pressure::PressureBody;
+pub use shapematch::ShapeBody;
+pub use timestep::FixedTimestep;
+pub use world::World;
+pub use zones::{Zone, ZoneKind, ZoneShape};
+
+pub const WIDTH: i32 = 800;
+pub const HEIGHT: i32 = 600;
+pub const CELL_SIZE: i32 = 50;
+pub const RADIUS_MIN: f64 = 10.0;
+pub const RADIUS_MAX: f64 = 20.0;
+pub const CIRCLE_NUMBER: usize = 30;
+pub const DEFAULT_DENSITY: f64 = 1.0;
+pub const PHYSICS_HZ: f64 = 60.0;
+pub const DEFAULT_SUBSTEPS: u32 = 1;
@@ -0,0 +1,165 @@
+use crate::circle::Circle;
+use crate::math::Double;
+use crate::parameters::Parameters;
+
+/// A damped spring between two circles.
+#[derive(Clone, Copy, Debug)]
+pub struct Link {
+    pub c1: usize,
+    pub c2: usize,
+    pub rest_length: f64,
+    /// Stretch, as a fraction of the rest length, beyond which the spring snaps.
+    pub max_strain: Option<f64>,
+    /// Stretch as a fraction of the rest length at the last break check.
+    pub strain: f64,
+}
+
+impl Link {
+    /// An unbreakable spring.
+    pub fn new(c1: usize, c2: usize, rest_length: f64) -> Link {
+        Link { c1, c2, rest_length, max_strain: None, strain: 0.0 }
+    }
+}
+
+/// A rigid distance constraint between two circles.
+#[derive(Clone, Copy, Debug)]
+pub struct StaticLink {
+    pub c1: usize,
+    pub c2: usize,
+    pub rest_length: f64,
+    /// Stretch, as a fraction of the rest length, beyond which the link snaps.
+    pub max_strain: Option<f64>,
+    /// Stretch as a fraction of the rest length at the last break check, taken
+    /// before the solver pulls the ends back together.
+    pub strain: f64,
+}
+
+impl StaticLink {
+    /// An unbreakable link.
+    pub fn new(c1: usize, c2: usize, rest_length: f64) -> StaticLink {
+        StaticLink { c1, c2, rest_length, max_strain: None, strain: 0.0 }
+    }
+}
+
+/// A bending constraint holding the angle at `c2` between the arms to `c1` and `c3`.
+#[derive(Clone, Copy, Debug)]
+pub struct AngleLink {
+    pub c1: usize,
+    pub c2: usize,
+    pub c3: usize,
+    /// Signed angle from the arm to `c1` round to the arm to `c3`, in radians.
+    pub rest_angle: f64,
+    /// Fraction of the angle error removed per solver pass, from 0 (limp) to 1 (rigid).
+    pub stiffness: f64,
+}
+
+impl AngleLink {
+    /// A constraint holding the angle the three circles currently make.
+    pub fn at_current_angle(circles: &[Circle], c1: usize, c2: usize, c3: usize, stiffness: f64) -> AngleLink {
+        let positions = [circles[c1].pinfo.pos, circles[c2].pinfo.pos, circles[c3].pinfo.pos];
+        AngleLink { c1, c2, c3, rest_angle: angle(positions), stiffness }
+    }
+}
+
+/// Signed angle at the middle point from the first arm round to the second.
+fn angle([p1, p2, p3]: [Double; 3]) -> f64 {
+    let (a, b) = (p1 - p2, p3 - p2);
+    a.cross(b).atan2(a.dot(b))
+}
+
+/// Stretch of a link between `c1` and `c2` as a fraction of `rest_length`;
+/// negative when compressed.
+pub fn strain(circles: &[Circle], c1: usize, c2: usize, rest_length: f64) -> f64 {
+    if rest_length <= 0.0 {
+        return 0.0;
+    }
+    (circles[c1].pinfo.pos.dist(circles[c2].pinfo.pos) - rest_length) / rest_length
+}
+
+/// A spring or link that snapped during a step.
+#[derive(Clone, Copy, Debug)]
+pub enum BrokenLink {
+    Spring(Link),
+    Static(StaticLink),
+}
+
+/// Adds the spring's pull on both ends to `acc`. Springs act per unit mass,
+/// so equal-mass rings behave the same at any density.
+pub fn apply_spring_force(pos: &[Double], vel: &[Double], acc: &mut [Double], link: &Link, params: &Parameters) {
+    if let Some(force) = spring_force(pos, vel, link, params) {
+        acc[link.c1] = acc[link.c1] + force;
+        acc[link.c2] = acc[link.c2] - force;
+    }
+}
+
+/// Pull of the spring on its first end; the second end feels the opposite.
+/// `None` when both ends sit on the same spot and there is no direction to pull in.
+pub fn spring_force(pos: &[Double], vel: &[Double], link: &Link, params: &Parameters) -> Option<Double> {
+    let (c1, c2) = (link.c1, link.c2);
+    let displacement = pos[c2] - pos[c1];
+    let distance = displacement.magnitude();
+    if distance == 0.0 {
+        return None;
+    }
+    let direction = displacement * (1.0 / distance);
+    let spring_force = (distance - link.rest_length) * params.spring_const;
+    let damping_force = (vel[c2] - vel[c1]) * params.damp_const * 0.1;
+    Some(direction * spring_force + damping_force)
+}
+
+/// Projects both ends along the link axis until it is back at its rest length,
+/// moving each end in proportion to its inverse mass. Velocities are left
+/// alone; the world turns the total correction into velocity afterwards.
+pub fn apply_static_link(circles: &mut [Circle], slink: &StaticLink) {
+    let (c1, c2) = (slink.c1, slink.c2);
+    let w1 = circles[c1].inv_mass();
+    let w2 = circles[c2].inv_mass();
+    let w_sum = w1 + w2;
+    if w_sum == 0.0 {
+        return;
+    }
+
+    let delta = circles[c2].pinfo.pos - circles[c1].pinfo.pos;
+    let distance = delta.magnitude();
+    if distance == 0.0 {
+        return;
+    }
+    let axis = delta * (1.0 / distance);
+    let error = distance - slink.rest_length;
+
+    circles[c1].pinfo.pos = circles[c1].pinfo.pos + axis * (error * w1 / w_sum);
+    circles[c2].pinfo.pos = circles[c2].pinfo.pos - axis * (error * w2 / w_sum);
+}
+
+/// Bends the three circles of an angle link back towards its rest angle,
+/// moving each in proportion to its inverse mass. Like static links it only
+/// moves positions.
+pub fn apply_angle_link(circles: &mut [Circle], alink: &AngleLink) {
+    let ids = [alink.c1, alink.c2, alink.c3];
+    let positions = ids.map(|id| circles[id].pinfo.pos);
+    let (a, b) = (positions[0] - positions[1], positions[2] - positions[1]);
+    let (a_sq, b_sq) = (a.dot(a), b.dot(b));
+    if a_sq == 0.0 || b_sq == 0.0 {
+        return;
+    }
+
+    // Gradient of the angle with respect to each circle's position
+    let grad1 = a.perp() * (-1.0 / a_sq);
+    let grad3 = b.perp() * (1.0 / b_sq);
+    let gradients = [grad1, (grad1 + grad3) * -1.0, grad3];
+
+    let weights = ids.map(|id| circles[id].inv_mass());
+    let k: f64 = (0..3).map(|i| weights[i] * gradients[i].dot(gradients[i])).sum();
+    if k == 0.0 {
+        return;
+    }
+
+    // Wrap the error so the joint bends back the short way round
+    let mut error = angle(positions) - alink.rest_angle;
+    error = (error + std::f64::consts::PI).rem_euclid(2.0 * std::f64::consts::PI) - std::f64::consts::PI;
+
+    for i in 0..3 {
+        let circle = &mut circles[ids[i]];
+        circle.pinfo.pos = circle.pinfo.pos - gradients[i] * (alink.stiffness * error * weights[i] / k);
+    }
+}
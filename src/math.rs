@@ -0,0 +1,57 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Double {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Double {
+    pub fn magnitude(&self) -> f64 {
+        (self.x * self.x + self.y * self.y).sqrt()
+    }
+    pub fn dist(&self, other: Double) -> f64 {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+        (dx * dx + dy * dy).sqrt()
+    }
+    pub fn dot(&self, other: Double) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+    /// z component of the 3D cross product.
+    pub fn cross(&self, other: Double) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+    /// This vector rotated a quarter turn.
+    pub fn perp(&self) -> Double {
+        Double { x: -self.y, y: self.x }
+    }
+}
+
+impl std::ops::Add for Double {
+    type Output = Double;
+    fn add(self, other: Double) -> Double {
+        Double {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl std::ops::Sub for Double {
+    type Output = Double;
+    fn sub(self, other: Double) -> Double {
+        Double {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl std::ops::Mul<f64> for Double {
+    type Output = Double;
+    fn mul(self, other: f64) -> Double {
+        Double {
+            x: self.x * other,
+            y: self.y * other,
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::math::Double;
+use crate::parallel::for_each_mut;
+
+/// Pull of a body of `mass` at offset `delta` under softened inverse-square gravity.
+fn softened_pull(delta: Double, mass: f64, grav_const: f64, softening: f64) -> Double {
+    let dist_sq = delta.dot(delta) + softening * softening;
+    if dist_sq == 0.0 {
+        return Double { x: 0.0, y: 0.0 };
+    }
+    delta * (grav_const * mass / (dist_sq * dist_sq.sqrt()))
+}
+
+/// Adds the mutual gravitational acceleration of every pair of bodies to `acc`, in O(n²).
+/// Each body sums the pulls on it in body order, shared out between up to `threads` threads.
+pub fn direct_accelerations(pos: &[Double], mass: &[f64], grav_const: f64, softening: f64, threads: usize, acc: &mut [Double]) {
+    for_each_mut(acc, threads, |i, acc| {
+        for j in (0..pos.len()).filter(|&j| j != i) {
+            *acc = *acc + softened_pull(pos[j] - pos[i], mass[j], grav_const, softening);
+        }
+    });
+}
+
+/// Adds the mutual gravitational acceleration to `acc` using a Barnes–Hut
+/// quadtree, in O(n log n). `theta` trades accuracy for speed; zero is exact.
+/// The tree walks are shared out between up to `threads` threads.
+pub fn barnes_hut_accelerations(pos: &[Double], mass: &[f64], grav_const: f64, softening: f64, theta: f64, threads: usize, acc: &mut [Double]) {
+    let tree = QuadTree::build(pos, mass);
+    for_each_mut(acc, threads, |i, acc| {
+        *acc = *acc + tree.acceleration(i, pos, mass, grav_const, softening, theta);
+    });
+}
+
+/// Depth after which coincident bodies share a leaf instead of splitting forever.
+const MAX_DEPTH: usize = 32;
+
+struct Node {
+    min: Double,
+    size: f64,
+    mass: f64,
+    center_of_mass: Double,
+    children: Option<[usize; 4]>,
+    bodies: Vec<usize>,
+}
+
+impl Node {
+    fn new(min: Double, size: f64) -> Node {
+        Node {
+            min,
+            size,
+            mass: 0.0,
+            center_of_mass: Double { x: 0.0, y: 0.0 },
+            children: None,
+            bodies: Vec::new(),
+        }
+    }
+
+    fn quadrant(&self, point: Double) -> usize {
+        let half = self.size / 2.0;
+        let right = point.x >= self.min.x + half;
+        let below = point.y >= self.min.y + half;
+        right as usize + 2 * below as usize
+    }
+}
+
+struct QuadTree {
+    nodes: Vec<Node>,
+}
+
+impl QuadTree {
+    fn build(pos: &[Double], mass: &[f64]) -> QuadTree {
+        let mut min = Double { x: f64::INFINITY, y: f64::INFINITY };
+        let mut max = Double { x: f64::NEG_INFINITY, y: f64::NEG_INFINITY };
+        for p in pos {
+            min = Double { x: min.x.min(p.x), y: min.y.min(p.y) };
+            max = Double { x: max.x.max(p.x), y: max.y.max(p.y) };
+        }
+        let size = (max.x - min.x).max(max.y - min.y).max(1.0) * 1.0001;
+
+        let mut tree = QuadTree { nodes: vec![Node::new(min, size)] };
+        for i in 0..pos.len() {
+            tree.insert(0, i, pos, 0);
+        }
+        tree.summarise(0, pos, mass);
+        tree
+    }
+
+    fn insert(&mut self, node: usize, body: usize, pos: &[Double], depth: usize) {
+        if let Some(children) = self.nodes[node].children {
+            let quadrant = self.nodes[node].quadrant(pos[body]);
+            self.insert(children[quadrant], body, pos, depth + 1);
+            return;
+        }
+        if self.nodes[node].bodies.is_empty() || depth >= MAX_DEPTH {
+            self.nodes[node].bodies.push(body);
+            return;
+        }
+
+        // Split the occupied leaf and push both bodies down a level
+        let half = self.nodes[node].size / 2.0;
+        let min = self.nodes[node].min;
+        let mut children = [0; 4];
+        for (quadrant, child) in children.iter_mut().enumerate() {
+            let offset = Double { x: (quadrant % 2) as f64 * half, y: (quadrant / 2) as f64 * half };
+            *child = self.nodes.len();
+            self.nodes.push(Node::new(min + offset, half));
+        }
+        self.nodes[node].children = Some(children);
+        for resident in std::mem::take(&mut self.nodes[node].bodies) {
+            self.insert(node, resident, pos, depth);
+        }
+        self.insert(node, body, pos, depth);
+    }
+
+    fn summarise(&mut self, node: usize, pos: &[Double], mass: &[f64]) {
+        let mut total = 0.0;
+        let mut weighted = Double { x: 0.0, y: 0.0 };
+        if let Some(children) = self.nodes[node].children {
+            for child in children {
+                self.summarise(child, pos, mass);
+                total += self.nodes[child].mass;
+                weighted = weighted + self.nodes[child].center_of_mass * self.nodes[child].mass;
+            }
+        } else {
+            for &body in &self.nodes[node].bodies {
+                total += mass[body];
+                weighted = weighted + pos[body] * mass[body];
+            }
+        }
+        self.nodes[node].mass = total;
+        if total > 0.0 {
+            self.nodes[node].center_of_mass = weighted * (1.0 / total);
+        }
+    }
+
+    fn acceleration(&self, target: usize, pos: &[Double], mass: &[f64], grav_const: f64, softening: f64, theta: f64) -> Double {
+        let mut acc = Double { x: 0.0, y: 0.0 };
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.mass == 0.0 {
+                continue;
+            }
+            match node.children {
+                None => {
+                    for &body in &node.bodies {
+                        if body != target {
+                            acc = acc + softened_pull(pos[body] - pos[target], mass[body], grav_const, softening);
+                        }
+                    }
+                }
+                Some(children) => {
+                    let dist = node.center_of_mass.dist(pos[target]);
+                    if dist > 0.0 && node.size / dist < theta {
+                        acc = acc + softened_pull(node.center_of_mass - pos[target], node.mass, grav_const, softening);
+                    } else {
+                        stack.extend(children);
+                    }
+                }
+            }
+        }
+        acc
+    }
+}
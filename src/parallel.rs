@@ -0,0 +1,258 @@
+use std::ops::Range;
+use std::sync::{Barrier, Mutex, RwLock};
+use std::thread;
+
+use crate::circle::{Circle, PhysicsInfo};
+use crate::math::Double;
+
+/// Fewest items worth handing to a thread of their own; below this the cost
+/// of starting it outweighs the work.
+const MIN_PER_THREAD: usize = 64;
+
+/// Threads actually used for `len` items when up to `threads` are allowed.
+fn thread_count(len: usize, threads: usize) -> usize {
+    threads.min(len / MIN_PER_THREAD).max(1)
+}
+
+/// The `part`th of `parts` near-equal contiguous ranges covering `0..len`.
+fn chunk(len: usize, parts: usize, part: usize) -> Range<usize> {
+    len * part / parts..len * (part + 1) / parts
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Threads the latest `map_ranges` called from this thread was split over.
+    static LAST_PARTS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Runs `work` over contiguous ranges of `0..len` on up to `threads` threads
+/// and joins the results in range order, so the output is the same however
+/// the work was split.
+pub(crate) fn map_ranges<R, F>(len: usize, threads: usize, work: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(Range<usize>) -> Vec<R> + Sync,
+{
+    let parts = thread_count(len, threads);
+    #[cfg(test)]
+    LAST_PARTS.with(|last| last.set(parts));
+    if parts == 1 {
+        return work(0..len);
+    }
+    let work = &work;
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..parts).map(|part| scope.spawn(move || work(chunk(len, parts, part)))).collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    })
+}
+
+/// Calls `work` on every item of `items` with its index, spread over up to
+/// `threads` threads. Each item is only ever seen by one call.
+pub(crate) fn for_each_mut<T, F>(items: &mut [T], threads: usize, work: F)
+where
+    T: Send,
+    F: Fn(usize, &mut T) + Sync,
+{
+    let parts = thread_count(items.len(), threads);
+    if parts == 1 {
+        for (i, item) in items.iter_mut().enumerate() {
+            work(i, item);
+        }
+        return;
+    }
+    let size = items.len().div_ceil(parts);
+    let work = &work;
+    thread::scope(|scope| {
+        for (part, slice) in items.chunks_mut(size).enumerate() {
+            scope.spawn(move || {
+                for (i, item) in slice.iter_mut().enumerate() {
+                    work(part * size + i, item);
+                }
+            });
+        }
+    });
+}
+
+/// Adds to `acc` the `(circle, change)` entries `work` pushes for every item
+/// of `items`. The entries are worked out on up to `threads` threads but added
+/// in item order, so the sums match a single-threaded pass bit for bit.
+pub(crate) fn scatter_add<C, F>(acc: &mut [Double], items: &[C], threads: usize, work: F)
+where
+    C: Sync,
+    F: Fn(&C, &mut Vec<(usize, Double)>) + Sync,
+{
+    let entries = map_ranges(items.len(), threads, |range| {
+        let mut entries = Vec::new();
+        for item in &items[range] {
+            work(item, &mut entries);
+        }
+        entries
+    });
+    for (id, change) in entries {
+        acc[id] = acc[id] + change;
+    }
+}
+
+/// Greedily sorts `items` into batches in which no two items share a circle,
+/// keeping their original order within each batch. The batches depend only on
+/// the items, never on the thread count.
+pub(crate) fn color_batches<C, F>(items: Vec<C>, num_circles: usize, members: F) -> Vec<Vec<C>>
+where
+    F: Fn(&C) -> Vec<usize>,
+{
+    let mut used: Vec<Vec<usize>> = vec![Vec::new(); num_circles];
+    let mut batches: Vec<Vec<C>> = Vec::new();
+    for item in items {
+        let ids = members(&item);
+        let color = (0..).find(|color| ids.iter().all(|id| !used[*id].contains(color))).unwrap();
+        for id in ids {
+            used[id].push(color);
+        }
+        if color == batches.len() {
+            batches.push(Vec::new());
+        }
+        batches[color].push(item);
+    }
+    batches
+}
+
+/// Solves each batch of `schedule` in turn on up to `threads` threads. `solve`
+/// sees the circles as they stood at the start of the batch and pushes the
+/// new state of every circle it moves; the updates are applied once the whole
+/// batch is done. Since no two items in a batch share a circle, that matches
+/// solving the batch one item at a time, in any order.
+pub(crate) fn solve_batches<C, F>(circles: &mut Vec<Circle>, schedule: &[Vec<C>], threads: usize, solve: F)
+where
+    C: Sync,
+    F: Fn(&[Circle], &C, &mut Vec<(usize, PhysicsInfo)>) + Sync,
+{
+    let largest = schedule.iter().map(|batch| batch.len()).max().unwrap_or(0);
+    let parts = thread_count(largest, threads);
+    if parts == 1 {
+        let mut updates = Vec::new();
+        for batch in schedule {
+            for item in batch {
+                solve(circles, item, &mut updates);
+            }
+            for (id, pinfo) in updates.drain(..) {
+                circles[id].pinfo = pinfo;
+            }
+        }
+        return;
+    }
+
+    // Threads are started once for the whole schedule and meet at a barrier
+    // after every batch, where one of them applies everyone's updates
+    let lock = RwLock::new(std::mem::take(circles));
+    let outputs: Vec<Mutex<Vec<(usize, PhysicsInfo)>>> = (0..parts).map(|_| Mutex::new(Vec::new())).collect();
+    let barrier = Barrier::new(parts);
+    let (shared, outputs, barrier, solve) = (&lock, &outputs, &barrier, &solve);
+    thread::scope(|scope| {
+        for part in 0..parts {
+            scope.spawn(move || {
+                for batch in schedule {
+                    {
+                        let circles = shared.read().unwrap();
+                        let mut updates = outputs[part].lock().unwrap();
+                        for item in &batch[chunk(batch.len(), parts, part)] {
+                            solve(&circles, item, &mut updates);
+                        }
+                    }
+                    if barrier.wait().is_leader() {
+                        let mut circles = shared.write().unwrap();
+                        for output in outputs {
+                            for (id, pinfo) in output.lock().unwrap().drain(..) {
+                                circles[id].pinfo = pinfo;
+                            }
+                        }
+                    }
+                    barrier.wait();
+                }
+            });
+        }
+    });
+    *circles = lock.into_inner().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::LAST_PARTS;
+    use crate::broadphase::BroadPhase;
+    use crate::circle::Circle;
+    use crate::grid::Grid;
+    use crate::integrator::Integrator;
+    use crate::math::Double;
+    use crate::world::World;
+    use crate::{CELL_SIZE, HEIGHT, WIDTH};
+
+    /// Every circle's final position and velocity after running a scene that
+    /// uses each threaded part of the step on `threads` threads.
+    fn run(threads: usize, nbody: bool) -> Vec<[u64; 4]> {
+        let mut world = World::with_seed(11);
+        let params = world.params_mut();
+        params.threads = threads;
+        params.nbody = nbody;
+        params.barnes_hut = false;
+        params.coulomb_const = 2000.0;
+        params.integrator = Integrator::Rk4;
+        world.add_random_circles(300);
+        for i in 0..100 {
+            let pos = Double { x: 40.0 + (i % 20) as f64 * 36.0, y: 60.0 + (i / 20) as f64 * 30.0 };
+            world.add_circle(Circle::new(pos, 6.0, [1.0; 4]).with_charge(if i % 2 == 0 { 1.0 } else { -1.0 }));
+        }
+        world.add_cloth(Double { x: 100.0, y: 50.0 }, 12, 8, 12.0, true);
+        world.add_rope(Double { x: 600.0, y: 50.0 }, 200.0, 20, true);
+        world.add_spring_softbody(24, 60.0, 6.0, Double { x: 250.0, y: 300.0 });
+        world.add_pressure_body(24, 60.0, 6.0, Double { x: 550.0, y: 300.0 }, 20000.0);
+        world.add_shape_softbody(24, 50.0, 6.0, Double { x: 400.0, y: 200.0 }, 0.5);
+        world.add_fluid_block(Double { x: 300.0, y: 450.0 }, 12, 6);
+        for _ in 0..5 {
+            world.step(1.0 / 60.0);
+        }
+        world
+            .circles()
+            .iter()
+            .map(|circle| {
+                let pinfo = circle.pinfo;
+                [pinfo.pos.x.to_bits(), pinfo.pos.y.to_bits(), pinfo.vel.x.to_bits(), pinfo.vel.y.to_bits()]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn results_do_not_depend_on_the_thread_count() {
+        for nbody in [false, true] {
+            let two = run(2, nbody);
+            assert!(two.len() >= 8 * super::MIN_PER_THREAD);
+            assert_eq!(run(4, nbody), two);
+            assert_eq!(run(8, nbody), two);
+        }
+    }
+
+    #[test]
+    fn grid_searches_use_every_thread() {
+        let mut rng = ChaCha8Rng::seed_from_u64(25);
+        let circles: Vec<Circle> = (0..600)
+            .map(|_| Circle::new(Double { x: rng.gen_range(0.0..WIDTH as f64), y: rng.gen_range(0.0..HEIGHT as f64) }, 8.0, [1.0; 4]))
+            .collect();
+        let pos: Vec<Double> = circles.iter().map(|circle| circle.pinfo.pos).collect();
+        let mut grid = Grid::new(WIDTH, HEIGHT, CELL_SIZE);
+        grid.update(&circles);
+        let mut points = Grid::new(WIDTH, HEIGHT, CELL_SIZE);
+        for (id, pos) in pos.iter().enumerate() {
+            points.add_point(*pos, id);
+        }
+
+        let collisions = grid.pairs(&circles, 1);
+        let neighbours = points.pairs_within(&pos, 30.0, 1);
+        for threads in [2, 4, 8] {
+            assert_eq!(grid.pairs(&circles, threads), collisions);
+            assert_eq!(LAST_PARTS.with(|last| last.get()), threads);
+            assert_eq!(points.pairs_within(&pos, 30.0, threads), neighbours);
+            assert_eq!(LAST_PARTS.with(|last| last.get()), threads);
+        }
+    }
+}
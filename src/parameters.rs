@@ -0,0 +1,171 @@
+use crate::integrator::Integrator;
+use crate::math::Double;
+
+/// Tunable constants of one [`World`](crate::World).
+///
+/// Every world owns its own set, so several simulations can run side by side
+/// with different settings and a set can be swapped wholesale between steps.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parameters {
+    /// Uniform acceleration applied to every circle, in any direction.
+    pub gravity: Double,
+    pub speed_factor: f64,
+    pub air_resistance: f64,
+    pub collide_loss: f64,
+    /// Fraction of the normal approach speed kept after two circles collide.
+    pub restitution: f64,
+    /// Coulomb friction coefficients for circle and wall contacts.
+    pub static_friction: f64,
+    pub kinetic_friction: f64,
+    /// Mass per unit area for newly created circles.
+    pub density: f64,
+    pub spring_const: f64,
+    pub damp_const: f64,
+    pub default_radius: f64,
+    pub default_color: [f32; 4],
+    pub default_link_length: f64,
+    /// Solver passes over all static links per step.
+    pub link_iterations: usize,
+    pub integrator: Integrator,
+    /// Mutual inverse-square attraction between all circles.
+    pub nbody: bool,
+    pub grav_const: f64,
+    /// Length added in quadrature to every distance so close passes stay finite.
+    pub softening: f64,
+    /// Approximate distant groups with a Barnes–Hut tree instead of summing every pair.
+    pub barnes_hut: bool,
+    /// Barnes–Hut opening angle; smaller is more accurate.
+    pub theta: f64,
+    /// Touching free circles fuse into one, conserving mass and momentum.
+    pub merge_on_contact: bool,
+    /// Coulomb constant; zero switches electric forces off.
+    pub coulomb_const: f64,
+    /// Charged circles further apart than this do not interact.
+    pub coulomb_cutoff: f64,
+    /// Strain at which newly created springs and links snap; zero makes them unbreakable.
+    pub break_strain: f64,
+    /// Bending stiffness given to newly generated ropes and rings; zero leaves them limp.
+    pub bend_stiffness: f64,
+    /// Radius of newly spawned fluid particles.
+    pub fluid_radius: f64,
+    /// SPH smoothing length: fluid particles further apart do not interact.
+    pub fluid_smoothing: f64,
+    /// Density the fluid settles at; denser regions push outwards.
+    pub fluid_rest_density: f64,
+    /// How hard compressed fluid pushes back.
+    pub fluid_stiffness: f64,
+    pub fluid_viscosity: f64,
+    /// Split steps automatically so fast circles cannot tunnel through each other.
+    pub ccd: bool,
+    /// Most substeps continuous collision detection may split one step into.
+    /// Circles still too fast after that are swept for their time of impact.
+    pub ccd_max_substeps: u32,
+    /// Let islands of circles that have come to rest fall asleep.
+    pub sleep: bool,
+    /// Speed below which a circle counts as resting.
+    pub sleep_speed: f64,
+    /// Seconds a whole island must rest before it falls asleep.
+    pub sleep_delay: f64,
+    /// Worker threads for a step. With more than one, collisions and links are
+    /// solved in independent batches, which gives the same result for any
+    /// thread count above one but differs slightly from a single thread.
+    pub threads: usize,
+}
+
+impl Default for Parameters {
+    fn default() -> Parameters {
+        Parameters {
+            gravity: Double { x: 0.0, y: 9.8 },
+            speed_factor: 3.0,
+            air_resistance: 0.05,
+            collide_loss: 0.4,
+            restitution: 0.6,
+            static_friction: 0.5,
+            kinetic_friction: 0.3,
+            density: 1.0,
+            spring_const: 0.1,
+            damp_const: 0.05,
+            default_radius: 10.0,
+            default_color: [1.0, 0.0, 0.0, 1.0],
+            default_link_length: 20.0,
+            link_iterations: 8,
+            integrator: Integrator::default(),
+            nbody: false,
+            grav_const: 200.0,
+            softening: 5.0,
+            barnes_hut: false,
+            theta: 0.5,
+            merge_on_contact: false,
+            coulomb_const: 200000.0,
+            coulomb_cutoff: 150.0,
+            break_strain: 0.0,
+            bend_stiffness: 0.0,
+            fluid_radius: 4.0,
+            fluid_smoothing: 16.0,
+            fluid_rest_density: 0.8,
+            fluid_stiffness: 10000.0,
+            fluid_viscosity: 40.0,
+            ccd: true,
+            ccd_max_substeps: 8,
+            sleep: true,
+            sleep_speed: 2.0,
+            sleep_delay: 1.0,
+            threads: 1,
+        }
+    }
+}
+
+impl Parameters {
+    /// Sets a parameter by its terminal name, returning `false` if the name is unknown.
+    pub fn set(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "gravity" => {self.gravity = Double { x: 0.0, y: value };}
+            "gravityx" => {self.gravity.x = value;}
+            "gravityy" => {self.gravity.y = value;}
+            "speedfactor" => {self.speed_factor = value;}
+            "airresistance" => {self.air_resistance = value;}
+            "collideloss" => {self.collide_loss = value;}
+            "restitution" => {self.restitution = value;}
+            "staticfriction" => {self.static_friction = value;}
+            "kineticfriction" => {self.kinetic_friction = value;}
+            "density" => {self.density = value;}
+            "springconst" => {self.spring_const = value;}
+            "dampconst" => {self.damp_const = value;}
+            "defaultradius" => {self.default_radius = value;}
+            "defaultcolorr" => {self.default_color[0] = value as f32;}
+            "defaultcolorg" => {self.default_color[1] = value as f32;}
+            "defaultcolorb" => {self.default_color[2] = value as f32;}
+            "defaultcolora" => {self.default_color[3] = value as f32;}
+            "defaultlinklength" => {self.default_link_length = value;}
+            "linkiterations" => {self.link_iterations = value.max(0.0) as usize;}
+            "nbody" => {self.nbody = value != 0.0;}
+            "gravconst" => {self.grav_const = value;}
+            "softening" => {self.softening = value;}
+            "barneshut" => {self.barnes_hut = value != 0.0;}
+            "theta" => {self.theta = value;}
+            "merge" => {self.merge_on_contact = value != 0.0;}
+            "coulombconst" => {self.coulomb_const = value;}
+            "coulombcutoff" => {self.coulomb_cutoff = value;}
+            "breakstrain" => {self.break_strain = value;}
+            "bendstiffness" => {self.bend_stiffness = value.clamp(0.0, 1.0);}
+            "fluidradius" => {self.fluid_radius = value;}
+            "fluidsmoothing" => {self.fluid_smoothing = value;}
+            "fluidrestdensity" => {self.fluid_rest_density = value;}
+            "fluidstiffness" => {self.fluid_stiffness = value;}
+            "fluidviscosity" => {self.fluid_viscosity = value;}
+            "ccd" => {self.ccd = value != 0.0;}
+            "ccdmaxsubsteps" => {self.ccd_max_substeps = value.max(1.0) as u32;}
+            "sleep" => {self.sleep = value != 0.0;}
+            "sleepspeed" => {self.sleep_speed = value;}
+            "sleepdelay" => {self.sleep_delay = value;}
+            "threads" => {self.threads = value.max(1.0) as usize;}
+            _ => {return false;}
+        }
+        true
+    }
+
+    /// `break_strain` as a limit for new links, or `None` when they should never break.
+    pub fn break_limit(&self) -> Option<f64> {
+        (self.break_strain > 0.0).then_some(self.break_strain)
+    }
+}
@@ -0,0 +1,65 @@
+use crate::math::Double;
+
+/// A closed ring of circles filled with gas. The pressure inside follows the
+/// ideal gas law, `pressure = gas_amount / area`, so squeezing the ring pushes
+/// back harder.
+#[derive(Clone, Debug)]
+pub struct PressureBody {
+    /// Circle indices in order round the ring.
+    pub nodes: Vec<usize>,
+    /// Amount of gas inside, the product nRT of the ideal gas law.
+    pub gas_amount: f64,
+}
+
+/// Smallest area used for the pressure, so a ring crushed flat stays finite.
+const MIN_AREA: f64 = 1.0;
+
+impl PressureBody {
+    pub fn new(nodes: Vec<usize>, gas_amount: f64) -> PressureBody {
+        PressureBody { nodes, gas_amount }
+    }
+
+    /// Signed area enclosed by the ring at positions `pos`, positive when the
+    /// nodes run anticlockwise in x-right, y-up terms.
+    pub fn signed_area(&self, pos: &[Double]) -> f64 {
+        let num = self.nodes.len();
+        (0..num)
+            .map(|i| pos[self.nodes[i]].cross(pos[self.nodes[(i + 1) % num]]))
+            .sum::<f64>()
+            / 2.0
+    }
+
+    pub fn pressure(&self, pos: &[Double]) -> f64 {
+        self.gas_amount / self.signed_area(pos).abs().max(MIN_AREA)
+    }
+
+    /// Adds the gas push on every edge to `acc`, split evenly between the
+    /// edge's two nodes. Like springs it acts per unit mass.
+    pub fn apply_pressure(&self, pos: &[Double], acc: &mut [Double]) {
+        let mut pushes = Vec::new();
+        self.pushes(pos, &mut pushes);
+        for (node, push) in pushes {
+            acc[node] = acc[node] + push;
+        }
+    }
+
+    /// Pushes `(node, push)` for both nodes of every edge, in edge order.
+    pub(crate) fn pushes(&self, pos: &[Double], pushes: &mut Vec<(usize, Double)>) {
+        let num = self.nodes.len();
+        if num < 3 {
+            return;
+        }
+        let area = self.signed_area(pos);
+        let pressure = self.gas_amount / area.abs().max(MIN_AREA);
+        let outward = if area >= 0.0 { 1.0 } else { -1.0 };
+
+        for i in 0..num {
+            let (a, b) = (self.nodes[i], self.nodes[(i + 1) % num]);
+            let edge = pos[b] - pos[a];
+            // Edge normal scaled by its length, so the push grows with the edge
+            let push = Double { x: edge.y, y: -edge.x } * (0.5 * pressure * outward);
+            pushes.push((a, push));
+            pushes.push((b, push));
+        }
+    }
+}
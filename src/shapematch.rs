@@ -0,0 +1,109 @@
+use crate::circle::Circle;
+use crate::math::Double;
+use crate::PHYSICS_HZ;
+
+/// A group of circles that remembers its rest shape and is pulled back
+/// towards the best rigid fit of that shape every step.
+#[derive(Clone, Debug)]
+pub struct ShapeBody {
+    pub nodes: Vec<usize>,
+    /// Rest position of each node relative to the rest centre of mass.
+    pub rest: Vec<Double>,
+    /// Fraction of the way towards the fitted shape each node moves per
+    /// `1 / PHYSICS_HZ` seconds, from 0 (limp) to 1 (rigid).
+    pub stiffness: f64,
+}
+
+/// Mass-weighted centre of `nodes`, or their plain average when none has mass.
+fn centre_of_mass(circles: &[Circle], nodes: &[usize]) -> Double {
+    let total: f64 = nodes.iter().map(|node| circles[*node].mass).sum();
+    if total > 0.0 {
+        nodes.iter().fold(Double { x: 0.0, y: 0.0 }, |sum, node| sum + circles[*node].pinfo.pos * circles[*node].mass) * (1.0 / total)
+    } else {
+        nodes.iter().fold(Double { x: 0.0, y: 0.0 }, |sum, node| sum + circles[*node].pinfo.pos) * (1.0 / nodes.len() as f64)
+    }
+}
+
+impl ShapeBody {
+    /// A body whose rest shape is the current arrangement of `nodes`.
+    pub fn from_current(circles: &[Circle], nodes: Vec<usize>, stiffness: f64) -> ShapeBody {
+        let centre = centre_of_mass(circles, &nodes);
+        let rest = nodes.iter().map(|node| circles[*node].pinfo.pos - centre).collect();
+        ShapeBody { nodes, rest, stiffness }
+    }
+
+    /// Where every node would sit if the rest shape were moved and turned to
+    /// best match the current positions.
+    pub fn goal_positions(&self, circles: &[Circle]) -> Vec<Double> {
+        if self.nodes.is_empty() {
+            return Vec::new();
+        }
+        let centre = centre_of_mass(circles, &self.nodes);
+
+        // In 2D the optimal rotation reduces to one angle
+        let (mut sin, mut cos) = (0.0, 0.0);
+        for (node, rest) in self.nodes.iter().zip(&self.rest) {
+            let offset = circles[*node].pinfo.pos - centre;
+            let mass = circles[*node].mass.max(f64::MIN_POSITIVE);
+            sin += mass * rest.cross(offset);
+            cos += mass * rest.dot(offset);
+        }
+        let (sin, cos) = if sin == 0.0 && cos == 0.0 { (0.0, 1.0) } else { sin.atan2(cos).sin_cos() };
+
+        self.rest
+            .iter()
+            .map(|rest| centre + Double { x: rest.x * cos - rest.y * sin, y: rest.x * sin + rest.y * cos })
+            .collect()
+    }
+
+    /// Moves every free node towards its goal position, changing its velocity
+    /// to match so the pull carries over into the next step. The pull is
+    /// scaled to `dt` so it compounds to `stiffness` every `1 / PHYSICS_HZ`
+    /// seconds, keeping the body equally firm however the time is split.
+    pub fn apply_shape_matching(&self, circles: &mut [Circle], dt: f64) {
+        let stiffness = 1.0 - (1.0 - self.stiffness.clamp(0.0, 1.0)).powf(dt * PHYSICS_HZ);
+        let goals = self.goal_positions(circles);
+        for (node, goal) in self.nodes.iter().zip(goals) {
+            let circle = &mut circles[*node];
+            if circle.inv_mass() == 0.0 {
+                continue;
+            }
+            let correction = (goal - circle.pinfo.pos) * stiffness;
+            circle.pinfo.pos = circle.pinfo.pos + correction;
+            circle.pinfo.vel = circle.pinfo.vel + correction * (1.0 / dt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pull_does_not_depend_on_how_time_is_split() {
+        let centre = Double { x: 400.0, y: 300.0 };
+        let mut circles: Vec<Circle> = (0..8)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::TAU / 8.0;
+                Circle::new(centre + Double { x: angle.cos(), y: angle.sin() } * 40.0, 5.0, [1.0; 4])
+            })
+            .collect();
+        let body = ShapeBody::from_current(&circles, (0..8).collect(), 0.3);
+        // Shrinking evenly keeps the best fit where it was, so only the pull moves the nodes
+        for circle in &mut circles {
+            circle.pinfo.pos = centre + (circle.pinfo.pos - centre) * 0.5;
+        }
+
+        let mut whole = circles.clone();
+        body.apply_shape_matching(&mut whole, 1.0 / PHYSICS_HZ);
+        let mut split = circles.clone();
+        for _ in 0..4 {
+            body.apply_shape_matching(&mut split, 0.25 / PHYSICS_HZ);
+        }
+
+        assert!(whole[0].pinfo.pos.dist(circles[0].pinfo.pos) > 1.0);
+        for (whole, split) in whole.iter().zip(&split) {
+            assert!(whole.pinfo.pos.dist(split.pinfo.pos) < 1e-9, "{:?} against {:?}", whole.pinfo.pos, split.pinfo.pos);
+        }
+    }
+}
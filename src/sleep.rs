@@ -0,0 +1,146 @@
+use crate::circle::Circle;
+use crate::math::Double;
+use crate::parameters::Parameters;
+use crate::world::World;
+
+/// Disjoint sets over circle indices, used to group circles into islands.
+struct Islands {
+    parent: Vec<usize>,
+}
+
+impl Islands {
+    fn new(num: usize) -> Islands {
+        Islands { parent: (0..num).collect() }
+    }
+
+    fn root(&mut self, mut id: usize) -> usize {
+        while self.parent[id] != id {
+            self.parent[id] = self.parent[self.parent[id]];
+            id = self.parent[id];
+        }
+        id
+    }
+
+    fn join(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.root(a), self.root(b));
+        // Always keep the lower root so the grouping does not depend on join order
+        if a < b {
+            self.parent[b] = a;
+        } else {
+            self.parent[a] = b;
+        }
+    }
+}
+
+/// Whether a circle may ever fall asleep. Circles feeling forces from
+/// everything around them, not just what touches them, stay awake.
+fn can_sleep(circle: &Circle, params: &Parameters) -> bool {
+    !circle.is_dragged
+        && !params.nbody
+        && !circle.fluid
+        && (circle.charge == 0.0 || params.coulomb_const == 0.0)
+}
+
+/// Whether any of the circles `ids` sleeps. Islands sleep as a whole, so a
+/// link or body with a sleeping member has nothing awake left to move.
+pub(crate) fn asleep(circles: &[Circle], ids: &[usize]) -> bool {
+    ids.iter().any(|id| circles[*id].sleeping)
+}
+
+impl World {
+    /// Times how long every circle has been nearly still, then puts to sleep
+    /// each island of touching or linked circles that has all been still for
+    /// `sleep_delay`. Islands with any moving member are woken instead.
+    pub(crate) fn update_sleep(&mut self, dt: f64) {
+        let params = &self.params;
+        if !params.sleep {
+            return;
+        }
+        for circle in &mut self.circles {
+            let speed = circle.pinfo.vel.magnitude().max(circle.pinfo.ang_vel.abs() * circle.radius);
+            if can_sleep(circle, params) && speed < params.sleep_speed {
+                circle.sleep_time += dt;
+            } else {
+                circle.sleep_time = 0.0;
+            }
+        }
+
+        let mut islands = self.islands();
+        let mut restless = vec![false; self.circles.len()];
+        for id in 0..self.circles.len() {
+            if self.circles[id].sleep_time < params.sleep_delay {
+                let root = islands.root(id);
+                restless[root] = true;
+            }
+        }
+        for id in 0..self.circles.len() {
+            let root = islands.root(id);
+            let circle = &mut self.circles[id];
+            circle.sleeping = !restless[root] && !circle.pinned;
+            if circle.sleeping {
+                circle.pinfo.vel = Double { x: 0.0, y: 0.0 };
+                circle.pinfo.ang_vel = 0.0;
+            }
+        }
+    }
+
+    /// Groups circles into islands joined by contacts, links and bodies.
+    /// Pinned circles do not join islands, so a pile resting on a pinned
+    /// circle can sleep separately from another pile on the same pin, unless
+    /// the pin is being dragged and has to wake everything it moves.
+    fn islands(&self) -> Islands {
+        let mut islands = Islands::new(self.circles.len());
+        let mut edges: Vec<(usize, usize)> = self.contacts.iter().map(|contact| (contact.a, contact.b)).collect();
+        edges.extend(self.links.iter().map(|link| (link.c1, link.c2)));
+        edges.extend(self.staticlinks.iter().map(|slink| (slink.c1, slink.c2)));
+        for alink in &self.anglelinks {
+            edges.push((alink.c1, alink.c2));
+            edges.push((alink.c2, alink.c3));
+        }
+        for nodes in self.pressurebodies.iter().map(|body| &body.nodes).chain(self.shapebodies.iter().map(|body| &body.nodes)) {
+            edges.extend(nodes.windows(2).map(|pair| (pair[0], pair[1])));
+        }
+        for (a, b) in edges {
+            let free = |id: usize| self.circles.get(id).is_some_and(|circle| !circle.pinned || circle.is_dragged);
+            if free(a) && free(b) {
+                islands.join(a, b);
+            }
+        }
+        islands
+    }
+
+    /// Wakes every sleeping island that an awake circle has joined since the
+    /// last step, so contacts are never resolved against a circle that will
+    /// not move. Pinned circles only wake their island while dragged.
+    pub(crate) fn wake_touched_islands(&mut self) {
+        if !self.circles.iter().any(|circle| circle.sleeping) {
+            return;
+        }
+        let mut islands = self.islands();
+        let mut restless = vec![false; self.circles.len()];
+        for id in 0..self.circles.len() {
+            let circle = &self.circles[id];
+            if !circle.sleeping && (!circle.pinned || circle.is_dragged) {
+                let root = islands.root(id);
+                restless[root] = true;
+            }
+        }
+        for id in 0..self.circles.len() {
+            if self.circles[id].sleeping && restless[islands.root(id)] {
+                self.circles[id].wake();
+            }
+        }
+    }
+
+    /// Wakes every circle and restarts its stillness timer.
+    pub fn wake_all(&mut self) {
+        for circle in &mut self.circles {
+            circle.wake();
+        }
+    }
+
+    /// Number of circles currently asleep.
+    pub fn sleeping_count(&self) -> usize {
+        self.circles.iter().filter(|circle| circle.sleeping).count()
+    }
+}
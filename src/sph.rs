@@ -0,0 +1,87 @@
+use std::f64::consts::PI;
+
+use crate::grid::Grid;
+use crate::math::Double;
+use crate::parameters::Parameters;
+use crate::{CELL_SIZE, HEIGHT, WIDTH};
+
+/// Poly6 kernel, used to sum densities.
+fn poly6(dist_sq: f64, h: f64) -> f64 {
+    let h_sq = h * h;
+    if dist_sq >= h_sq {
+        return 0.0;
+    }
+    4.0 / (PI * h.powi(8)) * (h_sq - dist_sq).powi(3)
+}
+
+/// Magnitude of the spiky kernel's gradient, used for pressure.
+fn spiky_gradient(dist: f64, h: f64) -> f64 {
+    if dist >= h {
+        return 0.0;
+    }
+    -30.0 / (PI * h.powi(5)) * (h - dist).powi(2)
+}
+
+/// Laplacian of the viscosity kernel.
+fn viscosity_laplacian(dist: f64, h: f64) -> f64 {
+    if dist >= h {
+        return 0.0;
+    }
+    40.0 / (PI * h.powi(5)) * (h - dist)
+}
+
+/// Pairs of fluid particles closer than the smoothing length.
+fn neighbour_pairs(pos: &[Double], fluid: &[usize], h: f64, threads: usize) -> Vec<(usize, usize)> {
+    let mut grid = Grid::new(WIDTH, HEIGHT, CELL_SIZE);
+    for &id in fluid {
+        grid.add_point(pos[id], id);
+    }
+    grid.pairs_within(pos, h, threads)
+}
+
+/// Density at every circle; zero for circles that are not among `fluid`.
+pub fn densities(pos: &[Double], mass: &[f64], fluid: &[usize], params: &Parameters) -> Vec<f64> {
+    let h = params.fluid_smoothing;
+    let mut density = vec![0.0; pos.len()];
+    for &id in fluid {
+        density[id] = mass[id] * poly6(0.0, h);
+    }
+    for (i, j) in neighbour_pairs(pos, fluid, h, params.threads) {
+        let w = poly6(pos[i].dist(pos[j]).powi(2), h);
+        density[i] += mass[j] * w;
+        density[j] += mass[i] * w;
+    }
+    density
+}
+
+/// Adds the pressure and viscosity accelerations between the `fluid`
+/// particles to `acc`.
+pub fn sph_accelerations(pos: &[Double], vel: &[Double], mass: &[f64], fluid: &[usize], params: &Parameters, acc: &mut [Double]) {
+    if fluid.is_empty() || params.fluid_smoothing <= 0.0 {
+        return;
+    }
+    let h = params.fluid_smoothing;
+    let density = densities(pos, mass, fluid, params);
+    // Only compression pushes back, so the fluid does not clump into droplets
+    let pressure: Vec<f64> = density
+        .iter()
+        .map(|rho| (params.fluid_stiffness * (rho - params.fluid_rest_density)).max(0.0))
+        .collect();
+
+    for (i, j) in neighbour_pairs(pos, fluid, h, params.threads) {
+        let delta = pos[i] - pos[j];
+        let dist = delta.magnitude();
+        if dist == 0.0 || density[i] == 0.0 || density[j] == 0.0 {
+            continue;
+        }
+        let direction = delta * (1.0 / dist);
+
+        // Symmetric pressure force, pushing the pair apart when compressed
+        let push = -(pressure[i] + pressure[j]) / 2.0 * spiky_gradient(dist, h);
+        let viscosity = (vel[j] - vel[i]) * (params.fluid_viscosity * viscosity_laplacian(dist, h));
+
+        let force_on_i = direction * push + viscosity;
+        acc[i] = acc[i] + force_on_i * (mass[j] / (density[i] * density[j]));
+        acc[j] = acc[j] - force_on_i * (mass[i] / (density[i] * density[j]));
+    }
+}
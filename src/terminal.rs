@@ -0,0 +1,1081 @@
+use std::f64::consts::PI;
+
+use piston_window::*;
+use rusty_physics::{AngleLink, BroadPhase, Circle, Double, FixedTimestep, Grid, Integrator, Link, Parameters, StaticLink, SweepAndPrune, World, Zone, ZoneKind, ZoneShape, CELL_SIZE, CIRCLE_NUMBER, HEIGHT, WIDTH};
+
+/// Gas amount given to pressure bodies when no `-pressure` flag is passed.
+const DEFAULT_PRESSURE: f64 = 20000.0;
+
+/// Stiffness given to shape-matching bodies when no `-stiffness` flag is passed.
+const DEFAULT_SHAPE_STIFFNESS: f64 = 0.3;
+
+pub struct UserTerminal {
+    pub display_text: String,
+    pub input_text: String,
+    pub cursor_pos: Double,
+    pub cursor_mode: String,
+    /// Shade fluid particles by their SPH density instead of their colour.
+    pub density_colors: bool,
+    /// Draw sleeping circles dimmed.
+    pub sleep_colors: bool,
+}
+/*
+User Terminal Commands:
+    help | Display types of help texts
+        help text | Display commands that run on terminal texts
+            help circle
+                circle -radius -r -g -b -a -x -y -mass -charge | Create a circle with (flags) radius radius and color R G B A at position X Y, mass defaults to area * density, charge defaults to 0
+            help softbody
+                softbody -circlenum -radius -subradius -x -y | Create a softbody with (flags) num number of circles with radius radius and subradius subradius at position X Y
+            help springbody
+                springbody -circlenum -radius -subradius -x -y | Create a springbody with (flags) num number of circles with radius radius and subradius subradius at position X Y
+            help pressurebody
+                pressurebody -circlenum -radius -subradius -pressure -x -y | Create a softbody filled with gas at pressure (gas amount) pressure, which keeps its area and bounces like a balloon
+            help shapebody
+                shapebody -circlenum -radius -subradius -x -y -stiffness | Create a softbody that pulls its circles back towards its rest shape with stiffness from 0 to 1
+            help rope
+                rope -ropelength -segmentnum -pin -x -y | Create a rope with (flags) length length and num number of segments at position X Y, pinning the first node if pin is 1
+            help cloth
+                cloth -width -height -spacing -pin -x -y | Create a width by height lattice of circles spacing apart with its top left corner at X Y, braced by structural, shear and bend links, pinning the top row if pin is 1
+            help fluid
+                fluid -width -height -x -y | Create a width by height block of fluid particles with its top left corner at X Y
+                fluiddisc -radius -x -y | Fill a disc of radius radius at X Y with fluid particles
+                fluidcolor | Toggle shading fluid particles by density, blue where sparse through to red where compressed
+                default fluidradius/fluidsmoothing/fluidrestdensity/fluidstiffness/fluidviscosity VALUE | Tune the fluid
+            help filter
+                filter N -group -layers -mask | Set the collision group, layer bits and mask bits of circle number N; circles sharing a nonzero group never collide, and a pair collides only if each one's layers meet the other's mask
+                    (ropes, softbodies and cloths get a group of their own when they are made)
+            help pin
+                pin N | Pin circle number N in place
+                unpin N | Release circle number N
+            help default
+                default PARAMETER VALUE | Change a parameter live (gravity sets straight-down gravity, gravityx and gravityy set each component)
+                default reset | Restore every parameter to its default
+            help nbody
+                default nbody 1 | Make every circle attract every other with softened inverse-square gravity
+                default barneshut 1 | Approximate distant groups with a Barnes-Hut tree (accuracy set by default theta)
+                default merge 1 | Fuse touching free circles instead of bouncing them
+            help charge
+                circle -charge Q | Give a new circle charge Q, like charges repel and opposite charges attract
+                default coulombconst K | Strength of the electric force, 0 switches it off
+                default coulombcutoff D | Charged circles further apart than D do not interact
+            help zone
+                wind -fx -fy -x -y -w -h -radius | Create a zone at X Y pushing circles inside it with acceleration FX FY
+                vortex -strength -x -y -w -h -radius | Create a zone swirling circles around its centre, clockwise for positive strength
+                radial -strength -x -y -w -h -radius | Create a zone pushing circles away from its centre, or pulling them in for negative strength
+                drag -strength -x -y -w -h -radius | Create a zone slowing circles inside it in proportion to their speed
+                    (zones are W by H rectangles unless -radius is given, which makes them circles)
+                zones | List every zone with its number
+                removezone N | Remove zone number N
+            help bend
+                bend N1 N2 N3 -stiffness -angle | Hold the angle at circle N2 between circles N1 and N3, at its current angle unless -angle gives one in degrees
+                default bendstiffness S | Ropes, softbodies and springbodies made afterwards resist bending with stiffness S from 0 to 1, 0 leaves them limp
+            help break
+                default breakstrain S | Springs and links made afterwards snap once stretched by more than S times their rest length, 0 never snaps
+            help substeps
+                substeps N | Split every fixed physics step into N substeps
+                default ccd 1 | Also split steps automatically when circles move fast enough to pass through each other, up to default ccdmaxsubsteps, then sweep any still too fast for their time of impact
+            help seed
+                seed | Display the current random seed
+                seed N | Clear the scene and respawn the random circles from seed N
+            help integrator
+                integrator euler/verlet/rk4 | Switch between semi-implicit Euler, Velocity Verlet and RK4
+                energy | Display the kinetic, potential and total energy of the world
+            help contacts
+                contacts | Display how many pairs touched during the last step, the deepest overlap and the fastest impact
+            help sleep
+                sleepcolor | Toggle drawing sleeping circles dimmed
+                sleeping | Display how many circles are asleep
+                default sleep 0/1 | Let piles that have rested for default sleepdelay seconds below default sleepspeed fall asleep
+            help threads
+                default threads N | Share each step between N worker threads; results are the same for any N above 1, though they differ slightly from a single thread
+            help broadphase
+                broadphase grid/sap | Find colliding pairs with the uniform grid or by sweep and prune along x
+
+        help mouse | Display commands that change modes for the mouse
+            help circlemode
+                circlemode -radius -r -g -b -a -charge | Change mouse mode to make circles with (flags) radius radius, color R G B A and charge Q
+            help softbodymode
+                softbodymode -circlenum -radius -subradius | Change mouse mode to make softbodies with (flags) num number of circles with radius radius and subradius subradius
+            help springbodymode
+                springbodymode -circlenum -radius -subradius | Change mouse mode to make springbodies with (flags) num number of circles with radius radius and subradius subradius
+            help pressurebodymode
+                pressurebodymode -circlenum -radius -subradius -pressure | Change mouse mode to make pressurebodies with (flags) num number of circles with radius radius, subradius subradius and gas pressure pressure
+            help shapebodymode
+                shapebodymode -circlenum -radius -subradius -stiffness | Change mouse mode to make shapebodies with (flags) num number of circles with radius radius, subradius subradius and stiffness stiffness
+            help ropemode
+                ropemode -ropelength -segmentnum -pin | Change mouse mode to make ropes with (flags) length length and num number of segments, pinning the first node if pin is 1
+            help clothmode
+                clothmode -width -height -spacing -pin | Change mouse mode to make cloths with (flags) width by height circles spacing apart, pinning the top row if pin is 1
+            help pinmode
+                pinmode | Change mouse mode to pin or unpin the clicked circle
+            help springmode
+                springmode | Change mouse mode to make springs between circles
+            help fluidmode
+                fluidmode -radius | Change mouse mode to paint fluid particles with a brush of radius radius while the mouse is held
+*/
+impl UserTerminal {
+    pub fn right_click(&mut self) {
+        self.cursor_mode = String::from("");
+        self.display_text = "Cursor Mode Cleared".to_string();
+    }
+
+    pub fn eval_cursor_release(&mut self, world: &mut World) {
+        let default_link_length = world.params().default_link_length;
+        if self.cursor_mode.starts_with("spring") {
+            let mut args = self.cursor_mode.split(",");
+            args.next();
+            let Some(n1) = args.next().map(|n| n.parse::<usize>().unwrap()) else {
+                return;
+            };
+            if let Some(n2) = world.circle_at(self.cursor_pos).filter(|n2| *n2 != n1) {
+                world.add_link(Link {
+                    max_strain: world.params().break_limit(),
+                    ..Link::new(n1, n2, default_link_length)
+                });
+                println!("SPRINGMODE: Creating spring between circle {} and circle {} with rest length: {}", n1, n2, default_link_length);
+                self.display_text = format!("Made spring with default rest length: circle {} and circle {}", n1, n2);
+            }
+        } else if self.cursor_mode.starts_with("link") {
+            let mut args = self.cursor_mode.split(",");
+            args.next();
+            let Some(n1) = args.next().map(|n| n.parse::<usize>().unwrap()) else {
+                return;
+            };
+            if let Some(n2) = world.circle_at(self.cursor_pos).filter(|n2| *n2 != n1) {
+                let d = world.circles()[n1].pinfo.pos.dist(world.circles()[n2].pinfo.pos);
+                world.add_static_link(StaticLink {
+                    max_strain: world.params().break_limit(),
+                    ..StaticLink::new(n1, n2, d)
+                });
+                println!("LINKMODE: Creating link between circle {} and circle {} with rest length: {}", n1, n2, d);
+                self.display_text = format!("Made link with rest length {}: circle {} and circle {}", d, n1, n2);
+            }
+        }
+    }
+
+    /// Called as the cursor moves with the left button held.
+    pub fn eval_cursor_drag(&mut self, world: &mut World) {
+        if self.cursor_mode.starts_with("fluid") {
+            self.paint_fluid(world);
+        }
+    }
+
+    fn paint_fluid(&mut self, world: &mut World) {
+        let radius: f64 = self.cursor_mode.split(",").nth(1).unwrap().parse().unwrap();
+        let before = world.circles().len();
+        world.fill_fluid_disc(self.cursor_pos, radius);
+        self.display_text = format!("Painted fluid: {} particles", world.circles().len() - before);
+    }
+
+    pub fn eval_cursor_click(&mut self, world: &mut World) {
+        if self.cursor_mode.starts_with("circle") {
+            let mut args = self.cursor_mode.split(",");
+            args.next();
+            let radius = args.next().unwrap().parse().unwrap();
+            let r = args.next().unwrap().parse().unwrap();
+            let g = args.next().unwrap().parse().unwrap();
+            let b = args.next().unwrap().parse().unwrap();
+            let a = args.next().unwrap().parse().unwrap();
+            let charge = args.next().unwrap().parse().unwrap();
+            world.add_circle(Circle::new(self.cursor_pos, radius, [r, g, b, a]).with_density(world.params().density).with_charge(charge));
+            println!("CIRCLEMODE: Creating circle with radius: {}, color: {:?}, charge: {}", radius, [r, g, b, a], charge);
+            self.display_text = format!("Made circle: radius: {}, color: {:?}, charge: {}, x: {}, y: {}", radius, [r, g, b, a], charge, self.cursor_pos.x, self.cursor_pos.y);
+        } else if self.cursor_mode.starts_with("softbody") || self.cursor_mode.starts_with("springbody") || self.cursor_mode.starts_with("pressurebody") || self.cursor_mode.starts_with("shapebody") {
+            let mut args = self.cursor_mode.split(",");
+            args.next();
+            let num = args.next().unwrap().parse().unwrap();
+            let radius = args.next().unwrap().parse().unwrap();
+            let subradius = args.next().unwrap().parse().unwrap();
+            if self.cursor_mode.starts_with("springbody") {
+                world.add_spring_softbody(num, radius, subradius, self.cursor_pos);
+                println!("SPRINGBODYMODE: Creating springbody with num: {}, radius: {}, subradius: {}", num, radius, subradius);
+                self.display_text = format!("Made springbody: circlenum: {}, radius: {}, subradius: {}, x: {}, y: {}", num, radius, subradius, self.cursor_pos.x, self.cursor_pos.y);
+            } else if self.cursor_mode.starts_with("shapebody") {
+                let stiffness = args.next().unwrap().parse().unwrap();
+                world.add_shape_softbody(num, radius, subradius, self.cursor_pos, stiffness);
+                println!("SHAPEBODYMODE: Creating shapebody with num: {}, radius: {}, subradius: {}, stiffness: {}", num, radius, subradius, stiffness);
+                self.display_text = format!("Made shapebody: circlenum: {}, radius: {}, subradius: {}, stiffness: {}, x: {}, y: {}", num, radius, subradius, stiffness, self.cursor_pos.x, self.cursor_pos.y);
+            } else if self.cursor_mode.starts_with("pressurebody") {
+                let pressure = args.next().unwrap().parse().unwrap();
+                world.add_pressure_body(num, radius, subradius, self.cursor_pos, pressure);
+                println!("PRESSUREBODYMODE: Creating pressurebody with num: {}, radius: {}, subradius: {}, pressure: {}", num, radius, subradius, pressure);
+                self.display_text = format!("Made pressurebody: circlenum: {}, radius: {}, subradius: {}, pressure: {}, x: {}, y: {}", num, radius, subradius, pressure, self.cursor_pos.x, self.cursor_pos.y);
+            } else {
+                world.add_softbody(num, radius, subradius, self.cursor_pos);
+                println!("SOFTBODYMODE: Creating softbody with num: {}, radius: {}, subradius: {}", num, radius, subradius);
+                self.display_text = format!("Made softbody: circlenum: {}, radius: {}, subradius: {}, x: {}, y: {}", num, radius, subradius, self.cursor_pos.x, self.cursor_pos.y);
+            }
+        } else if self.cursor_mode.starts_with("rope") {
+            let mut args = self.cursor_mode.split(",");
+            args.next();
+            let rope_length = args.next().unwrap().parse().unwrap();
+            let segment_num = args.next().unwrap().parse().unwrap();
+            let pin = args.next().unwrap().parse().unwrap();
+            world.add_rope(self.cursor_pos, rope_length, segment_num, pin);
+            println!("ROPEMODE: Creating rope with length: {}, segmentnum: {}, pin: {}", rope_length, segment_num, pin);
+            self.display_text = format!("Made rope: ropelength: {}, segmentnum: {}, pin: {}, x: {}, y: {}", rope_length, segment_num, pin, self.cursor_pos.x, self.cursor_pos.y);
+        } else if self.cursor_mode.starts_with("fluid") {
+            self.paint_fluid(world);
+        } else if self.cursor_mode.starts_with("cloth") {
+            let mut args = self.cursor_mode.split(",");
+            args.next();
+            let width = args.next().unwrap().parse().unwrap();
+            let height = args.next().unwrap().parse().unwrap();
+            let spacing = args.next().unwrap().parse().unwrap();
+            let pin = args.next().unwrap().parse().unwrap();
+            world.add_cloth(self.cursor_pos, width, height, spacing, pin);
+            println!("CLOTHMODE: Creating cloth with width: {}, height: {}, spacing: {}, pin: {}", width, height, spacing, pin);
+            self.display_text = format!("Made cloth: width: {}, height: {}, spacing: {}, pin: {}, x: {}, y: {}", width, height, spacing, pin, self.cursor_pos.x, self.cursor_pos.y);
+        } else if self.cursor_mode.starts_with("pin") {
+            if let Some(n) = world.circle_at(self.cursor_pos) {
+                let pinned = !world.circles()[n].pinned;
+                world.set_pinned(n, pinned);
+                println!("PINMODE: Setting circle {} pinned: {}", n, pinned);
+                self.display_text = format!("Circle {} pinned: {}", n, pinned);
+            }
+        } else if self.cursor_mode.starts_with("spring") {
+            if let Some(n) = world.circle_at(self.cursor_pos) {
+                self.cursor_mode = format!("spring,{}", n);
+            }
+        } else if self.cursor_mode.starts_with("link") {
+            if let Some(n) = world.circle_at(self.cursor_pos) {
+                self.cursor_mode = format!("link,{}", n);
+            }
+        }
+    }
+
+    pub fn eval_cursor_mode(&mut self, context: &Context, graphics: &mut G2d, world: &World) {
+        if self.cursor_mode.starts_with("circle") {
+            let mut args = self.cursor_mode.split(",");
+            args.next();
+            let radius: f64 = args.next().unwrap().parse().unwrap();
+            let r = args.next().unwrap().parse().unwrap();
+            let g = args.next().unwrap().parse().unwrap();
+            let b = args.next().unwrap().parse().unwrap();
+            let a: f32 = args.next().unwrap().parse().unwrap();
+
+            ellipse(
+                [r, g, b, a / 2.0],
+                [
+                    self.cursor_pos.x - radius,
+                    self.cursor_pos.y - radius,
+                    radius * 2.0,
+                    radius * 2.0,
+                ],
+                context.transform,
+                graphics,
+            );
+        } else if self.cursor_mode.starts_with("softbody") || self.cursor_mode.starts_with("springbody") || self.cursor_mode.starts_with("pressurebody") || self.cursor_mode.starts_with("shapebody") {
+            let mut args = self.cursor_mode.split(",");
+            args.next();
+            let num: usize = args.next().unwrap().parse().unwrap();
+            let radius: f64 = args.next().unwrap().parse().unwrap();
+            let subradius: f64 = args.next().unwrap().parse().unwrap();
+
+            for i in 0..num {
+                let start_x = self.cursor_pos.x + radius * (i as f64 * 2.0 * PI / num as f64).cos();
+                let start_y = self.cursor_pos.y + radius * (i as f64 * 2.0 * PI / num as f64).sin();
+                let end_x = self.cursor_pos.x + radius * ((i + 1) as f64 * 2.0 * PI / num as f64).cos();
+                let end_y = self.cursor_pos.y + radius * ((i + 1) as f64 * 2.0 * PI / num as f64).sin();
+
+                line (
+                    [0.0, 0.0, 0.0, 0.5],
+                    1.0,
+                    [start_x, start_y, end_x, end_y],
+                    context.transform,
+                    graphics,
+                );
+                ellipse (
+                    [1.0, 0.0, 0.0, 0.5],
+                    [
+                        self.cursor_pos.x + radius * (i as f64 * 2.0 * PI / num as f64).cos() - subradius,
+                        self.cursor_pos.y + radius * (i as f64 * 2.0 * PI / num as f64).sin() - subradius,
+                        subradius * 2.0,
+                        subradius * 2.0,
+                    ],
+                    context.transform,
+                    graphics,
+                );
+            }
+        } else if self.cursor_mode.starts_with("rope") {
+            let mut args = self.cursor_mode.split(",");
+            args.next();
+            let rope_length: f64 = args.next().unwrap().parse().unwrap();
+            let segment_num: i64 = args.next().unwrap().parse().unwrap();
+
+            for i in 0..segment_num+2 {
+                let start_x = self.cursor_pos.x + (i as f64 * rope_length / segment_num as f64) * 0.0;
+                let start_y = self.cursor_pos.y + (i as f64 * rope_length / segment_num as f64) * 1.0;
+                let end_x = self.cursor_pos.x + ((i + 1) as f64 * rope_length / segment_num as f64) * 0.0;
+                let end_y = self.cursor_pos.y + ((i + 1) as f64 * rope_length / segment_num as f64) * 1.0;
+
+                line (
+                    [0.0, 0.0, 0.0, 0.5],
+                    1.0,
+                    [start_x, start_y, end_x, end_y],
+                    context.transform,
+                    graphics,
+                );
+            }
+        } else if self.cursor_mode.starts_with("fluid") {
+            let radius: f64 = self.cursor_mode.split(",").nth(1).unwrap().parse().unwrap();
+            Ellipse::new_border([0.2, 0.4, 1.0, 0.5], 1.0).draw(
+                [self.cursor_pos.x - radius, self.cursor_pos.y - radius, radius * 2.0, radius * 2.0],
+                &context.draw_state,
+                context.transform,
+                graphics,
+            );
+        } else if self.cursor_mode.starts_with("cloth") {
+            let mut args = self.cursor_mode.split(",");
+            args.next();
+            let width: usize = args.next().unwrap().parse().unwrap();
+            let height: usize = args.next().unwrap().parse().unwrap();
+            let spacing: f64 = args.next().unwrap().parse().unwrap();
+            let right = self.cursor_pos.x + width.saturating_sub(1) as f64 * spacing;
+            let bottom = self.cursor_pos.y + height.saturating_sub(1) as f64 * spacing;
+
+            for x in 0..width {
+                let x = self.cursor_pos.x + x as f64 * spacing;
+                line([0.0, 0.0, 0.0, 0.5], 1.0, [x, self.cursor_pos.y, x, bottom], context.transform, graphics);
+            }
+            for y in 0..height {
+                let y = self.cursor_pos.y + y as f64 * spacing;
+                line([0.0, 0.0, 0.0, 0.5], 1.0, [self.cursor_pos.x, y, right, y], context.transform, graphics);
+            }
+        } else if self.cursor_mode.starts_with("spring") {
+            if self.cursor_mode == "spring" {
+                return;
+            }
+
+            let mut args = self.cursor_mode.split(",");
+            args.next();
+            let n: usize = args.next().unwrap().parse().unwrap();
+            line (
+                [0.0, 0.0, 0.0, 0.5],
+                1.0,
+                [self.cursor_pos.x, self.cursor_pos.y, world.circles()[n].pinfo.pos.x, world.circles()[n].pinfo.pos.y],
+                context.transform,
+                graphics,
+            );
+        } else if self.cursor_mode.starts_with("link") {
+            if self.cursor_mode == "link" {
+                return;
+            }
+
+            let mut args = self.cursor_mode.split(",");
+            args.next();
+            let n: usize = args.next().unwrap().parse().unwrap();
+            line (
+                [0.0, 0.0, 0.0, 0.5],
+                1.0,
+                [self.cursor_pos.x, self.cursor_pos.y, world.circles()[n].pinfo.pos.x, world.circles()[n].pinfo.pos.y],
+                context.transform,
+                graphics,
+            );
+        }
+    }
+
+    pub fn execute_input(&mut self, world: &mut World, timestep: &mut FixedTimestep) {
+        println!("Executing input: `{}`", self.input_text.trim());
+        let default_radius = world.params().default_radius;
+        let default_color = world.params().default_color;
+
+        match self.input_text.trim() {
+            "help" => {self.display_text = String::from("help text-Display text commands | help mouse-Display mouse commands");}
+
+            "help text" => {self.display_text = String::from("help +circle/softbody/springbody/pressurebody/shapebody/rope/cloth/fluid/filter/pin/default/nbody/charge/zone/break/bend/substeps/seed/integrator/broadphase/contacts/sleep/threads");}
+
+            "help circle" => {self.display_text = String::from("circle -radius -r -g -b -a -x -y -mass -charge");}
+            s if s.starts_with("circle ") || s == "circle" => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut radius = default_radius;
+                let mut color = default_color;
+                let mut pos = Double { x: WIDTH as f64 / 2.0, y: HEIGHT as f64 / 2.0 };
+                let mut mass = None;
+                let mut charge = 0.0;
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-radius" => {radius = args.next().unwrap().parse().unwrap();}
+                        "-r" => {color[0] = args.next().unwrap().parse().unwrap();}
+                        "-g" => {color[1] = args.next().unwrap().parse().unwrap();}
+                        "-b" => {color[2] = args.next().unwrap().parse().unwrap();}
+                        "-a" => {color[3] = args.next().unwrap().parse().unwrap();}
+                        "-x" => {pos.x = args.next().unwrap().parse().unwrap();}
+                        "-y" => {pos.y = args.next().unwrap().parse().unwrap();}
+                        "-mass" => {mass = Some(args.next().unwrap().parse().unwrap());}
+                        "-charge" => {charge = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Creating circle with radius: {}, color: {:?}, charge: {}", radius, color, charge);
+                self.display_text = format!("Made circle: radius: {}, color: {:?}, charge: {}, x: {}, y: {}", radius, color, charge, pos.x, pos.y);
+                let circle = Circle::new(pos, radius, color).with_density(world.params().density).with_charge(charge);
+                world.add_circle(match mass {
+                    Some(mass) => circle.with_mass(mass),
+                    None => circle,
+                });
+            }
+            "help softbody" => {self.display_text = String::from("softbody -circlenum -radius -subradius -x -y");}
+            s if s.starts_with("softbody ") || s == "softbody" => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut num = 10;
+                let mut radius = 100.0;
+                let mut subradius = 10.0;
+                let mut pos = Double { x: WIDTH as f64 / 2.0, y: HEIGHT as f64 / 2.0 };
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-circlenum" => {num = args.next().unwrap().parse().unwrap();}
+                        "-radius" => {radius = args.next().unwrap().parse().unwrap();}
+                        "-subradius" => {subradius = args.next().unwrap().parse().unwrap();}
+                        "-x" => {pos.x = args.next().unwrap().parse().unwrap();}
+                        "-y" => {pos.y = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Creating softbody with num: {}, radius: {}, subradius: {}", num, radius, subradius);
+                self.display_text = format!("Made softbody: circlenum: {}, radius: {}, subradius: {}, x: {}, y: {}", num, radius, subradius, pos.x, pos.y);
+                world.add_softbody(num, radius, subradius, pos);
+            }
+            "help pressurebody" => {self.display_text = String::from("pressurebody -circlenum -radius -subradius -pressure -x -y");}
+            s if s.starts_with("pressurebody ") || s == "pressurebody" => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut num = 16;
+                let mut radius = 50.0;
+                let mut subradius = 4.0;
+                let mut pressure = DEFAULT_PRESSURE;
+                let mut pos = Double { x: WIDTH as f64 / 2.0, y: HEIGHT as f64 / 2.0 };
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-circlenum" => {num = args.next().unwrap().parse().unwrap();}
+                        "-radius" => {radius = args.next().unwrap().parse().unwrap();}
+                        "-subradius" => {subradius = args.next().unwrap().parse().unwrap();}
+                        "-pressure" => {pressure = args.next().unwrap().parse().unwrap();}
+                        "-x" => {pos.x = args.next().unwrap().parse().unwrap();}
+                        "-y" => {pos.y = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Creating pressurebody with num: {}, radius: {}, subradius: {}, pressure: {}", num, radius, subradius, pressure);
+                self.display_text = format!("Made pressurebody: circlenum: {}, radius: {}, subradius: {}, pressure: {}, x: {}, y: {}", num, radius, subradius, pressure, pos.x, pos.y);
+                world.add_pressure_body(num, radius, subradius, pos, pressure);
+            }
+            "help shapebody" => {self.display_text = String::from("shapebody -circlenum -radius -subradius -x -y -stiffness");}
+            s if s.starts_with("shapebody ") || s == "shapebody" => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut num = 10;
+                let mut radius = 100.0;
+                let mut subradius = 10.0;
+                let mut pos = Double { x: WIDTH as f64 / 2.0, y: HEIGHT as f64 / 2.0 };
+                let mut stiffness = DEFAULT_SHAPE_STIFFNESS;
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-circlenum" => {num = args.next().unwrap().parse().unwrap();}
+                        "-radius" => {radius = args.next().unwrap().parse().unwrap();}
+                        "-subradius" => {subradius = args.next().unwrap().parse().unwrap();}
+                        "-x" => {pos.x = args.next().unwrap().parse().unwrap();}
+                        "-y" => {pos.y = args.next().unwrap().parse().unwrap();}
+                        "-stiffness" => {stiffness = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Creating shapebody with num: {}, radius: {}, subradius: {}, stiffness: {}", num, radius, subradius, stiffness);
+                self.display_text = format!("Made shapebody: circlenum: {}, radius: {}, subradius: {}, stiffness: {}, x: {}, y: {}", num, radius, subradius, stiffness, pos.x, pos.y);
+                world.add_shape_softbody(num, radius, subradius, pos, stiffness);
+            }
+            "help springbody" => {self.display_text = String::from("springbody -circlenum -radius -subradius -x -y");}
+            s if s.starts_with("springbody ") || s == "springbody" => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut num = 10;
+                let mut radius = 100.0;
+                let mut subradius = 10.0;
+                let mut pos = Double { x: WIDTH as f64 / 2.0, y: HEIGHT as f64 / 2.0 };
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-circlenum" => {num = args.next().unwrap().parse().unwrap();}
+                        "-radius" => {radius = args.next().unwrap().parse().unwrap();}
+                        "-subradius" => {subradius = args.next().unwrap().parse().unwrap();}
+                        "-x" => {pos.x = args.next().unwrap().parse().unwrap();}
+                        "-y" => {pos.y = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Creating springbody with num: {}, radius: {}, subradius: {}", num, radius, subradius);
+                self.display_text = format!("Made springbody: circlenum: {}, radius: {}, subradius: {}, x: {}, y: {}", num, radius, subradius, pos.x, pos.y);
+                world.add_spring_softbody(num, radius, subradius, pos);
+            }
+            "help cloth" => {self.display_text = String::from("cloth -width -height -spacing -pin -x -y");}
+            s if s.starts_with("cloth ") || s == "cloth" => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut width = 12;
+                let mut height = 10;
+                let mut spacing = 15.0;
+                let mut pin = true;
+                let mut pos = Double { x: WIDTH as f64 / 2.0, y: HEIGHT as f64 / 4.0 };
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-width" => {width = args.next().unwrap().parse().unwrap();}
+                        "-height" => {height = args.next().unwrap().parse().unwrap();}
+                        "-spacing" => {spacing = args.next().unwrap().parse().unwrap();}
+                        "-pin" => {pin = args.next().unwrap().parse::<i32>().unwrap() != 0;}
+                        "-x" => {pos.x = args.next().unwrap().parse().unwrap();}
+                        "-y" => {pos.y = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Creating cloth with width: {}, height: {}, spacing: {}, pin: {}", width, height, spacing, pin);
+                self.display_text = format!("Made cloth: width: {}, height: {}, spacing: {}, pin: {}, x: {}, y: {}", width, height, spacing, pin, pos.x, pos.y);
+                world.add_cloth(pos, width, height, spacing, pin);
+            }
+            "help fluid" => {self.display_text = String::from("fluid -width -height -x -y | fluiddisc -radius -x -y | fluidcolor (help default 8 to tune)");}
+            s if s.starts_with("fluid ") || s == "fluid" => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut width = 20;
+                let mut height = 15;
+                let mut pos = Double { x: 20.0, y: HEIGHT as f64 / 2.0 };
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-width" => {width = args.next().unwrap().parse().unwrap();}
+                        "-height" => {height = args.next().unwrap().parse().unwrap();}
+                        "-x" => {pos.x = args.next().unwrap().parse().unwrap();}
+                        "-y" => {pos.y = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Creating fluid block with width: {}, height: {}", width, height);
+                self.display_text = format!("Made fluid: width: {}, height: {}, x: {}, y: {}", width, height, pos.x, pos.y);
+                world.add_fluid_block(pos, width, height);
+            }
+            s if s.starts_with("fluiddisc") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut radius = 50.0;
+                let mut pos = Double { x: WIDTH as f64 / 2.0, y: HEIGHT as f64 / 2.0 };
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-radius" => {radius = args.next().unwrap().parse().unwrap();}
+                        "-x" => {pos.x = args.next().unwrap().parse().unwrap();}
+                        "-y" => {pos.y = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Creating fluid disc with radius: {}", radius);
+                self.display_text = format!("Made fluid disc: radius: {}, x: {}, y: {}", radius, pos.x, pos.y);
+                world.fill_fluid_disc(pos, radius);
+            }
+            "fluidcolor" => {
+                self.density_colors = !self.density_colors;
+                println!("Setting fluid density colors: {}", self.density_colors);
+                self.display_text = format!("Fluid density colors: {}", self.density_colors);
+            }
+            "help rope" => {self.display_text = String::from("rope -ropelength -segmentnum -pin -x -y");}
+            s if s.starts_with("rope ") || s == "rope" => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut rope_length = 100.0;
+                let mut segment_num = 10;
+                let mut pin = false;
+                let mut pos = Double { x: WIDTH as f64 / 2.0, y: HEIGHT as f64 / 2.0 };
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-ropelength" => {rope_length = args.next().unwrap().parse().unwrap();}
+                        "-segmentnum" => {segment_num = args.next().unwrap().parse().unwrap();}
+                        "-pin" => {pin = args.next().unwrap().parse::<i32>().unwrap() != 0;}
+                        "-x" => {pos.x = args.next().unwrap().parse().unwrap();}
+                        "-y" => {pos.y = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Creating rope with length: {}, segmentnum: {}, pin: {}", rope_length, segment_num, pin);
+                self.display_text = format!("Made rope: ropelength: {}, segmentnum: {}, pin: {}, x: {}, y: {}", rope_length, segment_num, pin, pos.x, pos.y);
+                world.add_rope(pos, rope_length, segment_num, pin);
+            }
+
+            "help default" => {self.display_text = String::from("default PARAMETER VALUE | default reset (help default 1-10 for parameters)");}
+            "help default 1" => {self.display_text = String::from("GRAVITY|GRAVITYX|GRAVITYY|SPEEDFACTOR|AIRRESISTANCE");}
+            "help default 2" => {self.display_text = String::from("COLLIDELOSS|RESTITUTION|DENSITY|SPRINGCONST|DAMPCONST");}
+            "help default 3" => {self.display_text = String::from("DEFAULTRADIUS|DEFAULTCOLORR|DEFAULTCOLORG|DEFAULTCOLORB");}
+            "help default 4" => {self.display_text = String::from("DEFAULTCOLORA|DEFAULTLINKLENGTH");}
+            "help default 5" => {self.display_text = String::from("STATICFRICTION|KINETICFRICTION|LINKITERATIONS");}
+            "help default 6" => {self.display_text = String::from("NBODY|GRAVCONST|SOFTENING|BARNESHUT|THETA|MERGE");}
+            "help default 7" => {self.display_text = String::from("COULOMBCONST|COULOMBCUTOFF|BREAKSTRAIN|BENDSTIFFNESS");}
+            "help default 8" => {self.display_text = String::from("FLUIDRADIUS|FLUIDSMOOTHING|FLUIDRESTDENSITY|FLUIDSTIFFNESS|FLUIDVISCOSITY");}
+            "help default 9" => {self.display_text = String::from("CCD|CCDMAXSUBSTEPS");}
+            "help default 10" => {self.display_text = String::from("SLEEP|SLEEPSPEED|SLEEPDELAY|THREADS");}
+            "help nbody" => {self.display_text = String::from("default nbody 1, default gravity 0, then gravconst/softening/barneshut/theta/merge");}
+            "help charge" => {self.display_text = String::from("circle -charge Q (or circlemode -charge Q), then default coulombconst/coulombcutoff");}
+            "default reset" => {
+                world.set_params(Parameters::default());
+                println!("Resetting default parameters");
+                self.display_text = String::from("Reset all parameters to their defaults");
+            }
+            s if s.starts_with("default") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let param = args.next().unwrap();
+                let value = args.next().unwrap().parse().unwrap();
+                if !world.params_mut().set(param, value) {
+                    println!("Invalid Parameter");
+                    self.display_text = format!("Invalid Parameter: {}", param);
+                } else {
+                    println!("Setting default parameter: {} to value: {}", param, value);
+                    self.display_text = format!("Setting default parameter: {} to value: {}", param, value);
+                }
+            }
+
+            "help zone" => {self.display_text = String::from("wind -fx -fy | vortex/radial/drag -strength, all with -x -y -w -h or -radius | zones | removezone N");}
+            s if ["wind", "vortex", "radial", "drag"].iter().any(|kind| s == *kind || s.starts_with(&format!("{} ", kind))) => {
+                let mut args = s.split_whitespace();
+                let name = args.next().unwrap();
+                let mut pos = Double { x: WIDTH as f64 / 2.0, y: HEIGHT as f64 / 2.0 };
+                let (mut width, mut height) = (200.0, 200.0);
+                let mut radius = None;
+                let mut force = Double { x: 20.0, y: 0.0 };
+                let mut strength = if name == "drag" { 1.0 } else { 50.0 };
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-x" => {pos.x = args.next().unwrap().parse().unwrap();}
+                        "-y" => {pos.y = args.next().unwrap().parse().unwrap();}
+                        "-w" => {width = args.next().unwrap().parse().unwrap();}
+                        "-h" => {height = args.next().unwrap().parse().unwrap();}
+                        "-radius" => {radius = Some(args.next().unwrap().parse().unwrap());}
+                        "-fx" => {force.x = args.next().unwrap().parse().unwrap();}
+                        "-fy" => {force.y = args.next().unwrap().parse().unwrap();}
+                        "-strength" => {strength = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                let shape = match radius {
+                    Some(radius) => ZoneShape::Circle { center: pos, radius },
+                    None => ZoneShape::rect(pos, width, height),
+                };
+                let kind = match name {
+                    "wind" => ZoneKind::Wind(force),
+                    "vortex" => ZoneKind::Vortex(strength),
+                    "radial" => ZoneKind::Radial(strength),
+                    _ => ZoneKind::Drag(strength),
+                };
+                let id = world.add_zone(Zone::new(shape, kind));
+                println!("Creating zone {}: {}", id, describe_zone(&world.zones()[id]));
+                self.display_text = format!("Made zone {}: {}", id, describe_zone(&world.zones()[id]));
+            }
+            "zones" => {
+                for (id, zone) in world.zones().iter().enumerate() {
+                    println!("Zone {}: {}", id, describe_zone(zone));
+                }
+                let names: Vec<String> = world.zones().iter().enumerate().map(|(id, zone)| format!("{} {}", id, zone.kind.name())).collect();
+                self.display_text = format!("Zones: {}", if names.is_empty() { String::from("none") } else { names.join(", ") });
+            }
+            s if s.starts_with("removezone ") => {
+                let n: usize = s.split_whitespace().nth(1).unwrap().parse().unwrap();
+                match world.remove_zone(n) {
+                    Some(zone) => {
+                        println!("Removing zone {}: {}", n, describe_zone(&zone));
+                        self.display_text = format!("Removed zone {}: {}", n, zone.kind.name());
+                    }
+                    None => {println!("Invalid Zone"); self.display_text = format!("Invalid Zone: {}", n);}
+                }
+            }
+
+            "help bend" => {self.display_text = String::from("bend N1 N2 N3 -stiffness -angle | default bendstiffness S (stiffen new ropes and rings)");}
+            s if s.starts_with("bend ") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let ids: Vec<usize> = args.by_ref().take(3).map(|n| n.parse().unwrap()).collect();
+                let mut stiffness = 1.0;
+                let mut angle = None;
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-stiffness" => {stiffness = args.next().unwrap().parse().unwrap();}
+                        "-angle" => {angle = Some(args.next().unwrap().parse::<f64>().unwrap().to_radians());}
+                        _ => {}
+                    }
+                }
+                if ids.len() < 3 || ids.iter().any(|id| *id >= world.circles().len()) {
+                    println!("Invalid Circle");
+                    self.display_text = format!("Invalid Circle in: {:?}", ids);
+                } else {
+                    let mut alink = AngleLink::at_current_angle(world.circles(), ids[0], ids[1], ids[2], stiffness);
+                    if let Some(angle) = angle {
+                        alink.rest_angle = angle;
+                    }
+                    world.add_angle_link(alink);
+                    println!("Creating angle link at circle {} with rest angle: {}, stiffness: {}", ids[1], alink.rest_angle.to_degrees(), stiffness);
+                    self.display_text = format!("Made angle link: circles {:?}, angle: {:.1}, stiffness: {}", ids, alink.rest_angle.to_degrees(), stiffness);
+                }
+            }
+            "help break" => {self.display_text = String::from("default breakstrain S (new springs and links snap past strain S, 0 never)");}
+            "help substeps" => {self.display_text = String::from("substeps N (physics substeps per fixed step) | default ccd 0/1 (automatic substeps for fast circles)");}
+            s if s.starts_with("substeps") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let substeps: u32 = args.next().unwrap().parse().unwrap();
+                timestep.substeps = substeps.max(1);
+                println!("Setting substeps to: {}", timestep.substeps);
+                self.display_text = format!("Substeps per step: {}", timestep.substeps);
+            }
+
+            "help seed" => {self.display_text = String::from("seed | seed N (restart the scene from seed N)");}
+            "seed" => {self.display_text = format!("Current seed: {}", world.seed());}
+            s if s.starts_with("seed ") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let seed: u64 = args.next().unwrap().parse().unwrap();
+                world.clear();
+                world.reseed(seed);
+                world.add_random_circles(CIRCLE_NUMBER);
+                println!("Restarting with seed: {}", seed);
+                self.display_text = format!("Restarted scene with seed: {}", seed);
+            }
+
+            "help filter" => {self.display_text = String::from("filter N -group -layers -mask (same nonzero group never collides)");}
+            s if s.starts_with("filter ") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let n: usize = args.next().unwrap().parse().unwrap();
+                match world.circle_mut(n) {
+                    Some(circle) => {
+                        while let Some(arg) = args.next() {
+                            match arg {
+                                "-group" => {circle.group = args.next().unwrap().parse().unwrap();}
+                                "-layers" => {circle.layers = args.next().unwrap().parse().unwrap();}
+                                "-mask" => {circle.mask = args.next().unwrap().parse().unwrap();}
+                                _ => {}
+                            }
+                        }
+                        println!("Setting circle {} group: {}, layers: {}, mask: {}", n, circle.group, circle.layers, circle.mask);
+                        self.display_text = format!("Circle {}: group: {}, layers: {}, mask: {}", n, circle.group, circle.layers, circle.mask);
+                    }
+                    None => {println!("Invalid Circle"); self.display_text = format!("Invalid Circle: {}", n);}
+                }
+            }
+            "help pin" => {self.display_text = String::from("pin N | unpin N (pin or release circle number N)");}
+            s if s.starts_with("pin ") || s.starts_with("unpin ") => {
+                let mut args = s.split_whitespace();
+                let pinned = args.next().unwrap() == "pin";
+                let n: usize = args.next().unwrap().parse().unwrap();
+                if world.set_pinned(n, pinned) {
+                    println!("Setting circle {} pinned: {}", n, pinned);
+                    self.display_text = format!("Circle {} pinned: {}", n, pinned);
+                } else {
+                    println!("Invalid Circle");
+                    self.display_text = format!("Invalid Circle: {}", n);
+                }
+            }
+            "help integrator" => {self.display_text = String::from("integrator euler/verlet/rk4 | energy");}
+            "integrator" => {self.display_text = format!("Current integrator: {}", world.params().integrator.name());}
+            s if s.starts_with("integrator ") => {
+                let name = s.split_whitespace().nth(1).unwrap();
+                match Integrator::from_name(name) {
+                    Some(integrator) => {
+                        world.params_mut().integrator = integrator;
+                        println!("Setting integrator to: {}", name);
+                        self.display_text = format!("Integrator: {}", name);
+                    }
+                    None => {println!("Invalid Integrator"); self.display_text = format!("Invalid Integrator: {}", name);}
+                }
+            }
+            "help contacts" => {self.display_text = String::from("contacts (pairs touching in the last step)");}
+            "contacts" => {
+                let contacts = world.contacts();
+                let deepest = contacts.iter().map(|contact| contact.depth).fold(0.0, f64::max);
+                let fastest = contacts.iter().map(|contact| contact.approach_speed()).fold(0.0, f64::max);
+                for contact in contacts {
+                    println!("Contact {}-{}: depth {:.3}, approach {:.3}", contact.a, contact.b, contact.depth, contact.approach_speed());
+                }
+                self.display_text = format!("Contacts: {}, deepest {:.2}, fastest impact {:.2}", contacts.len(), deepest, fastest);
+            }
+            "help sleep" => {self.display_text = String::from("sleepcolor | sleeping | default sleep 0/1 (help default 10 to tune)");}
+            "sleepcolor" => {
+                self.sleep_colors = !self.sleep_colors;
+                println!("Setting sleep colors: {}", self.sleep_colors);
+                self.display_text = format!("Sleep colors: {}", self.sleep_colors);
+            }
+            "sleeping" => {
+                println!("Sleeping circles: {}", world.sleeping_count());
+                self.display_text = format!("Sleeping circles: {} of {}", world.sleeping_count(), world.circles().len());
+            }
+            "help threads" => {self.display_text = String::from("default threads N (worker threads per step, 1 runs single-threaded)");}
+            "help broadphase" => {self.display_text = String::from("broadphase grid/sap (uniform grid or sweep and prune)");}
+            "broadphase" => {self.display_text = format!("Current broadphase: {}", world.broadphase().name());}
+            s if s.starts_with("broadphase ") => {
+                let name = s.split_whitespace().nth(1).unwrap();
+                let broadphase: Option<Box<dyn BroadPhase>> = match name {
+                    "grid" => Some(Box::new(Grid::new(WIDTH, HEIGHT, CELL_SIZE))),
+                    "sap" => Some(Box::new(SweepAndPrune::new())),
+                    _ => None,
+                };
+                match broadphase {
+                    Some(broadphase) => {
+                        world.set_broadphase(broadphase);
+                        println!("Setting broadphase to: {}", name);
+                        self.display_text = format!("Broadphase: {}", name);
+                    }
+                    None => {println!("Invalid Broadphase"); self.display_text = format!("Invalid Broadphase: {}", name);}
+                }
+            }
+            "energy" => {
+                self.display_text = format!(
+                    "Energy: kinetic {:.1}, potential {:.1}, total {:.1}",
+                    world.kinetic_energy(),
+                    world.potential_energy(),
+                    world.total_energy()
+                );
+            }
+
+            "help mouse" => {self.display_text = String::from("help +circlemode/softbodymode/springbodymode/pressurebodymode/shapebodymode/ropemode/clothmode/fluidmode/linkmode/springmode/pinmode");}
+
+            "help circlemode" => {self.display_text = String::from("circlemode -radius -r -g -b -a -charge");}
+            s if s.starts_with("circlemode") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut radius = default_radius;
+                let mut color = default_color;
+                let mut charge = 0.0;
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-radius" => {radius = args.next().unwrap().parse().unwrap();}
+                        "-r" => {color[0] = args.next().unwrap().parse().unwrap();}
+                        "-g" => {color[1] = args.next().unwrap().parse().unwrap();}
+                        "-b" => {color[2] = args.next().unwrap().parse().unwrap();}
+                        "-a" => {color[3] = args.next().unwrap().parse().unwrap();}
+                        "-charge" => {charge = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Changing cursor mode to circle with radius: {}, color: {:?}, charge: {}", radius, color, charge);
+                self.display_text = format!("Circle mode: radius: {}, color: {:?}, charge: {}", radius, color, charge);
+                self.cursor_mode = format!("circle,{},{},{},{},{},{}", radius, color[0], color[1], color[2], color[3], charge);
+            }
+            "help softbodymode" => {self.display_text = String::from("softbodymode -circlenum -radius -subradius");}
+            s if s.starts_with("softbodymode") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut num = 10;
+                let mut radius = 100.0;
+                let mut subradius = default_radius;
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-circlenum" => {num = args.next().unwrap().parse().unwrap();}
+                        "-radius" => {radius = args.next().unwrap().parse().unwrap();}
+                        "-subradius" => {subradius = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Changing cursor mode to softbody with num: {}, radius: {}, subradius: {}", num, radius, subradius);
+                self.display_text = format!("Softbody mode: circlenum: {}, radius: {}, subradius: {}", num, radius, subradius);
+                self.cursor_mode = format!("softbody,{},{},{}", num, radius, subradius);
+            }
+            "help pressurebodymode" => {self.display_text = String::from("pressurebodymode -circlenum -radius -subradius -pressure");}
+            s if s.starts_with("pressurebodymode") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut num = 16;
+                let mut radius = 50.0;
+                let mut subradius = 4.0;
+                let mut pressure = DEFAULT_PRESSURE;
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-circlenum" => {num = args.next().unwrap().parse().unwrap();}
+                        "-radius" => {radius = args.next().unwrap().parse().unwrap();}
+                        "-subradius" => {subradius = args.next().unwrap().parse().unwrap();}
+                        "-pressure" => {pressure = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Changing cursor mode to pressurebody with num: {}, radius: {}, subradius: {}, pressure: {}", num, radius, subradius, pressure);
+                self.display_text = format!("Pressurebody mode: circlenum: {}, radius: {}, subradius: {}, pressure: {}", num, radius, subradius, pressure);
+                self.cursor_mode = format!("pressurebody,{},{},{},{}", num, radius, subradius, pressure);
+            }
+            "help shapebodymode" => {self.display_text = String::from("shapebodymode -circlenum -radius -subradius -stiffness");}
+            s if s.starts_with("shapebodymode") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut num = 10;
+                let mut radius = 100.0;
+                let mut subradius = default_radius;
+                let mut stiffness = DEFAULT_SHAPE_STIFFNESS;
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-circlenum" => {num = args.next().unwrap().parse().unwrap();}
+                        "-radius" => {radius = args.next().unwrap().parse().unwrap();}
+                        "-subradius" => {subradius = args.next().unwrap().parse().unwrap();}
+                        "-stiffness" => {stiffness = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Changing cursor mode to shapebody with num: {}, radius: {}, subradius: {}, stiffness: {}", num, radius, subradius, stiffness);
+                self.display_text = format!("Shapebody mode: circlenum: {}, radius: {}, subradius: {}, stiffness: {}", num, radius, subradius, stiffness);
+                self.cursor_mode = format!("shapebody,{},{},{},{}", num, radius, subradius, stiffness);
+            }
+            "help springbodymode" => {self.display_text = String::from("springbodymode -circlenum -radius -subradius");}
+            s if s.starts_with("springbodymode") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut num = 10;
+                let mut radius = 100.0;
+                let mut subradius = default_radius;
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-circlenum" => {num = args.next().unwrap().parse().unwrap();}
+                        "-radius" => {radius = args.next().unwrap().parse().unwrap();}
+                        "-subradius" => {subradius = args.next().unwrap().parse().unwrap();}
+                        _ => {}
+                    }
+                }
+                println!("Changing cursor mode to springbody with num: {}, radius: {}, subradius: {}", num, radius, subradius);
+                self.display_text = format!("Springbody mode: circlenum: {}, radius: {}, subradius: {}", num, radius, subradius);
+                self.cursor_mode = format!("springbody,{},{},{}", num, radius, subradius);
+            }
+            "help ropemode" => {self.display_text = String::from("ropemode -ropelength -segmentnum -pin");}
+            s if s.starts_with("ropemode") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut rope_length = 100.0;
+                let mut segment_num = 10;
+                let mut pin = false;
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-ropelength" => {rope_length = args.next().unwrap().parse().unwrap();}
+                        "-segmentnum" => {segment_num = args.next().unwrap().parse().unwrap();}
+                        "-pin" => {pin = args.next().unwrap().parse::<i32>().unwrap() != 0;}
+                        _ => {}
+                    }
+                }
+                println!("Changing cursor mode to rope with length: {}, segmentnum: {}, pin: {}", rope_length, segment_num, pin);
+                self.display_text = format!("Rope mode: ropelength: {}, segmentnum: {}, pin: {}", rope_length, segment_num, pin);
+                self.cursor_mode = format!("rope,{},{},{}", rope_length, segment_num, pin);
+            }
+            "help clothmode" => {self.display_text = String::from("clothmode -width -height -spacing -pin");}
+            s if s.starts_with("clothmode") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut width = 12;
+                let mut height = 10;
+                let mut spacing = 15.0;
+                let mut pin = true;
+                while let Some(arg) = args.next() {
+                    match arg {
+                        "-width" => {width = args.next().unwrap().parse().unwrap();}
+                        "-height" => {height = args.next().unwrap().parse().unwrap();}
+                        "-spacing" => {spacing = args.next().unwrap().parse().unwrap();}
+                        "-pin" => {pin = args.next().unwrap().parse::<i32>().unwrap() != 0;}
+                        _ => {}
+                    }
+                }
+                println!("Changing cursor mode to cloth with width: {}, height: {}, spacing: {}, pin: {}", width, height, spacing, pin);
+                self.display_text = format!("Cloth mode: width: {}, height: {}, spacing: {}, pin: {}", width, height, spacing, pin);
+                self.cursor_mode = format!("cloth,{},{},{},{}", width, height, spacing, pin);
+            }
+            "help fluidmode" => {self.display_text = String::from("fluidmode -radius (hold the mouse to paint fluid)");}
+            s if s.starts_with("fluidmode") => {
+                let mut args = s.split_whitespace();
+                args.next();
+                let mut radius = 20.0;
+                while let Some(arg) = args.next() {
+                    if arg == "-radius" {
+                        radius = args.next().unwrap().parse().unwrap();
+                    }
+                }
+                println!("Changing cursor mode to fluid with radius: {}", radius);
+                self.display_text = format!("Fluid mode: radius: {}", radius);
+                self.cursor_mode = format!("fluid,{}", radius);
+            }
+            "help springmode" => {self.display_text = String::from("springmode");}
+            "springmode" => {
+                println!("Spring Mode Enabled");
+                self.display_text = String::from("Spring mode enabled");
+                self.cursor_mode = String::from("spring");
+            }
+            "help pinmode" => {self.display_text = String::from("pinmode (click a circle to pin or unpin it)");}
+            "pinmode" => {
+                println!("Pin Mode Enabled");
+                self.display_text = String::from("Pin mode enabled");
+                self.cursor_mode = String::from("pin");
+            }
+            "help linkmode" => {self.display_text = String::from("linkmode");}
+            "linkmode" => {
+                println!("Link Mode Enabled");
+                self.display_text = String::from("Link mode enabled");
+                self.cursor_mode = String::from("link");
+            }
+
+            _ => {println!("Invalid Command.");}
+        }
+
+        self.input_text.clear();
+    }
+
+    pub fn handle_events(&mut self, event: &Event, world: &mut World, timestep: &mut FixedTimestep) {
+        if let Some(Button::Keyboard(key)) = event.press_args() {
+            match key {
+                Key::Return => {self.execute_input(world, timestep);}
+                Key::Backspace => {self.input_text.pop();}
+                Key::A => {self.input_text.push('a');}
+                Key::B => {self.input_text.push('b');}
+                Key::C => {self.input_text.push('c');}
+                Key::D => {self.input_text.push('d');}
+                Key::E => {self.input_text.push('e');}
+                Key::F => {self.input_text.push('f');}
+                Key::G => {self.input_text.push('g');}
+                Key::H => {self.input_text.push('h');}
+                Key::I => {self.input_text.push('i');}
+                Key::J => {self.input_text.push('j');}
+                Key::K => {self.input_text.push('k');}
+                Key::L => {self.input_text.push('l');}
+                Key::M => {self.input_text.push('m');}
+                Key::N => {self.input_text.push('n');}
+                Key::O => {self.input_text.push('o');}
+                Key::P => {self.input_text.push('p');}
+                Key::Q => {self.input_text.push('q');}
+                Key::R => {self.input_text.push('r');}
+                Key::S => {self.input_text.push('s');}
+                Key::T => {self.input_text.push('t');}
+                Key::U => {self.input_text.push('u');}
+                Key::V => {self.input_text.push('v');}
+                Key::W => {self.input_text.push('w');}
+                Key::X => {self.input_text.push('x');}
+                Key::Y => {self.input_text.push('y');}
+                Key::Z => {self.input_text.push('z');}
+                Key::D0 => {self.input_text.push('0');}
+                Key::D1 => {self.input_text.push('1');}
+                Key::D2 => {self.input_text.push('2');}
+                Key::D3 => {self.input_text.push('3');}
+                Key::D4 => {self.input_text.push('4');}
+                Key::D5 => {self.input_text.push('5');}
+                Key::D6 => {self.input_text.push('6');}
+                Key::D7 => {self.input_text.push('7');}
+                Key::D8 => {self.input_text.push('8');}
+                Key::D9 => {self.input_text.push('9');}
+                Key::Space => {self.input_text.push(' ');}
+                Key::Minus => {self.input_text.push('-');}
+                Key::Period => {self.input_text.push('.');}
+                _ => {}
+            }
+        }
+        
+    }
+}
+
+/// One-line summary of a zone for the `zones` listing.
+fn describe_zone(zone: &Zone) -> String {
+    let kind = match zone.kind {
+        ZoneKind::Wind(force) => format!("wind {} {}", force.x, force.y),
+        ZoneKind::Vortex(strength) => format!("vortex {}", strength),
+        ZoneKind::Radial(strength) => format!("radial {}", strength),
+        ZoneKind::Drag(strength) => format!("drag {}", strength),
+    };
+    let shape = match zone.shape {
+        ZoneShape::Rect { min, max } => format!("rect {}x{}", max.x - min.x, max.y - min.y),
+        ZoneShape::Circle { radius, .. } => format!("circle radius {}", radius),
+    };
+    let center = zone.shape.center();
+    format!("{}, {} at {} {}", kind, shape, center.x, center.y)
+}
@@ -0,0 +1,52 @@
+use crate::world::World;
+
+/// Drives a [`World`] at a fixed rate regardless of how often frames arrive.
+///
+/// Elapsed wall time is banked in an accumulator and spent in whole steps of
+/// `dt`, each split into `substeps` calls to [`World::step`]. Whatever is left
+/// over is exposed as [`FixedTimestep::alpha`] so rendering can interpolate
+/// between the last two physics states.
+#[derive(Clone, Debug)]
+pub struct FixedTimestep {
+    pub dt: f64,
+    pub substeps: u32,
+    /// Upper bound on steps per call, so a long stall cannot snowball.
+    pub max_steps: u32,
+    accumulator: f64,
+}
+
+impl FixedTimestep {
+    pub fn new(dt: f64, substeps: u32) -> FixedTimestep {
+        FixedTimestep {
+            dt,
+            substeps: substeps.max(1),
+            max_steps: 8,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Banks `elapsed` seconds and runs as many fixed steps as fit, returning how many ran.
+    pub fn advance(&mut self, world: &mut World, elapsed: f64) -> u32 {
+        self.accumulator += elapsed;
+
+        let sub_dt = self.dt / self.substeps as f64;
+        let mut steps = 0;
+        while self.accumulator >= self.dt && steps < self.max_steps {
+            world.store_previous_positions();
+            for _ in 0..self.substeps {
+                world.step(sub_dt);
+            }
+            self.accumulator -= self.dt;
+            steps += 1;
+        }
+        if steps == self.max_steps {
+            self.accumulator = self.accumulator.min(self.dt);
+        }
+        steps
+    }
+
+    /// Fraction of a step left in the accumulator, in `0.0..=1.0`.
+    pub fn alpha(&self) -> f64 {
+        (self.accumulator / self.dt).clamp(0.0, 1.0)
+    }
+}
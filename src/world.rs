@@ -0,0 +1,959 @@
+use std::collections::HashSet;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::broadphase::BroadPhase;
+use crate::circle::{coincident, resolve_collision, resolve_contact, time_of_impact, Circle, Contact};
+use crate::generators::{create_bending, create_cloth, create_ring, create_rope, create_softbody, create_spring_softbody};
+use crate::grid::Grid;
+use crate::links::{apply_angle_link, apply_static_link, strain, AngleLink, BrokenLink, Link, StaticLink};
+use crate::math::Double;
+use crate::parallel::{color_batches, solve_batches};
+use crate::parameters::Parameters;
+use crate::pressure::PressureBody;
+use crate::shapematch::ShapeBody;
+use crate::sleep::asleep;
+use crate::sph::densities;
+use crate::zones::Zone;
+use crate::{CELL_SIZE, HEIGHT, RADIUS_MAX, RADIUS_MIN, WIDTH};
+
+/// Colour of fluid particles when they are not shaded by density.
+const FLUID_COLOR: [f32; 4] = [0.2, 0.4, 1.0, 1.0];
+
+/// Fraction of the smaller radius by which swept circles are left overlapping,
+/// so the ordinary collision response sees them touch.
+const SWEEP_OVERLAP: f64 = 0.05;
+
+/// A link to solve in a batch of the threaded step.
+#[derive(Clone, Copy)]
+enum Constraint {
+    Static(StaticLink),
+    Angle(AngleLink),
+}
+
+/// All simulated state: circles, springs, rigid and bending links, pressure
+/// and shape-matching bodies, force-field zones and the collision broadphase.
+///
+/// Circles are addressed by their index in [`World::circles`]. Removing a
+/// circle shifts every later index down by one and drops the links that
+/// referenced it.
+///
+/// All randomness is drawn from a seeded generator owned by the world, so two
+/// worlds built with the same seed and fed the same inputs evolve identically.
+pub struct World {
+    pub(crate) circles: Vec<Circle>,
+    pub(crate) links: Vec<Link>,
+    pub(crate) staticlinks: Vec<StaticLink>,
+    pub(crate) anglelinks: Vec<AngleLink>,
+    pub(crate) pressurebodies: Vec<PressureBody>,
+    pub(crate) shapebodies: Vec<ShapeBody>,
+    pub(crate) zones: Vec<Zone>,
+    pub(crate) broken: Vec<BrokenLink>,
+    pub(crate) contacts: Vec<Contact>,
+    pub(crate) broadphase: Box<dyn BroadPhase>,
+    pub(crate) mouse_pos: Double,
+    pub(crate) params: Parameters,
+    pub(crate) seed: u64,
+    /// Next collision group handed to a generated body.
+    pub(crate) next_group: u32,
+    /// ChaCha8 promises the same stream for a seed across rand releases,
+    /// unlike `StdRng`, so seeded runs replay exactly.
+    pub(crate) rng: ChaCha8Rng,
+}
+
+impl Default for World {
+    fn default() -> World {
+        World::new()
+    }
+}
+
+impl World {
+    /// An empty world with default parameters and a random seed.
+    pub fn new() -> World {
+        World::with_seed(rand::random())
+    }
+
+    pub fn with_seed(seed: u64) -> World {
+        World::with_params(Parameters::default(), seed)
+    }
+
+    pub fn with_params(params: Parameters, seed: u64) -> World {
+        World {
+            circles: Vec::new(),
+            links: Vec::new(),
+            staticlinks: Vec::new(),
+            anglelinks: Vec::new(),
+            pressurebodies: Vec::new(),
+            shapebodies: Vec::new(),
+            zones: Vec::new(),
+            broken: Vec::new(),
+            contacts: Vec::new(),
+            broadphase: Box::new(Grid::new(WIDTH, HEIGHT, CELL_SIZE)),
+            mouse_pos: Double { x: 0.0, y: 0.0 },
+            params,
+            seed,
+            next_group: 1,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Advances the simulation by `dt` seconds, scaled by `speed_factor`.
+    ///
+    /// With continuous collision detection on, the step is split into as many
+    /// substeps as it takes for no circle to move far enough to pass through
+    /// another without the two ever overlapping.
+    pub fn step(&mut self, dt: f64) {
+        let dt = dt * self.params.speed_factor;
+        if dt <= 0.0 {
+            return;
+        }
+
+        self.contacts.clear();
+        let substeps = self.ccd_substeps(dt);
+        for _ in 0..substeps {
+            self.substep(dt / substeps as f64);
+        }
+
+        // A pair touching over several substeps is kept at its first impact
+        let mut seen = HashSet::new();
+        self.contacts.retain(|contact| seen.insert((contact.a, contact.b)));
+        self.update_sleep(dt);
+    }
+
+    /// Every pair of circles that touched during the last step, in the order
+    /// they were resolved. Indices refer to the circles as they were then.
+    pub fn contacts(&self) -> &[Contact] {
+        &self.contacts
+    }
+
+    /// Substeps needed so every circle moves at most half of its own radius
+    /// plus the smallest radius in the world, capped at `ccd_max_substeps`.
+    fn ccd_substeps(&self, dt: f64) -> u32 {
+        if !self.params.ccd || self.circles.is_empty() {
+            return 1;
+        }
+        let smallest = self.circles.iter().map(|circle| circle.radius).fold(f64::INFINITY, f64::min);
+        let worst = self
+            .circles
+            .iter()
+            .filter(|circle| circle.inv_mass() > 0.0)
+            .map(|circle| circle.pinfo.vel.magnitude() * dt / (0.5 * (circle.radius + smallest)).max(f64::MIN_POSITIVE))
+            .fold(0.0, f64::max);
+        (worst.ceil() as u32).clamp(1, self.params.ccd_max_substeps.max(1))
+    }
+
+    /// Catches the circles that still move too far in one substep once
+    /// `ccd_max_substeps` is reached. Any that would have met another circle
+    /// part way through the substep, given their straight paths from `start`,
+    /// are moved back to just after the first such meeting, so the collision
+    /// response sees them touch instead of letting them pass through.
+    fn sweep_fast_circles(&mut self, start: &[Double]) {
+        let circles = &self.circles;
+        let smallest = circles.iter().map(|circle| circle.radius).fold(f64::INFINITY, f64::min);
+        let fast: Vec<usize> = (0..circles.len())
+            .filter(|&i| circles[i].inv_mass() > 0.0 && circles[i].pinfo.pos.dist(start[i]) > 0.5 * (circles[i].radius + smallest))
+            .collect();
+        if fast.is_empty() {
+            return;
+        }
+
+        // Bounding box of each circle's whole path, to skip pairs that never come near
+        let swept = |i: usize| {
+            let reach = Double { x: circles[i].radius, y: circles[i].radius };
+            let (from, to) = (start[i], circles[i].pinfo.pos);
+            let min = Double { x: from.x.min(to.x), y: from.y.min(to.y) } - reach;
+            let max = Double { x: from.x.max(to.x), y: from.y.max(to.y) } + reach;
+            (min, max)
+        };
+        let mut impact = vec![1.0_f64; circles.len()];
+        for &i in &fast {
+            let (min_i, max_i) = swept(i);
+            for j in 0..circles.len() {
+                // Pairs of two fast circles were already tried from the lower one
+                if j == i || (j < i && fast.binary_search(&j).is_ok()) {
+                    continue;
+                }
+                let (min_j, max_j) = swept(j);
+                if min_i.x > max_j.x || min_j.x > max_i.x || min_i.y > max_j.y || min_j.y > max_i.y {
+                    continue;
+                }
+                if !circles[i].can_collide(&circles[j]) {
+                    continue;
+                }
+                let reach = circles[i].radius + circles[j].radius - SWEEP_OVERLAP * circles[i].radius.min(circles[j].radius);
+                if let Some(t) = time_of_impact(start[i], circles[i].pinfo.pos, start[j], circles[j].pinfo.pos, reach) {
+                    impact[i] = impact[i].min(t);
+                    impact[j] = impact[j].min(t);
+                }
+            }
+        }
+
+        for (i, circle) in self.circles.iter_mut().enumerate() {
+            if impact[i] < 1.0 {
+                circle.pinfo.pos = start[i] + (circle.pinfo.pos - start[i]) * impact[i];
+            }
+        }
+    }
+
+    fn substep(&mut self, dt: f64) {
+        let start: Vec<Double> = if self.params.ccd { self.circles.iter().map(|circle| circle.pinfo.pos).collect() } else { Vec::new() };
+        self.integrate(dt);
+
+        let params = &self.params;
+        for circle in self.circles.iter_mut().filter(|circle| !circle.sleeping) {
+            circle.collide_walls(params);
+        }
+        if self.params.ccd {
+            self.sweep_fast_circles(&start);
+        }
+
+        self.broadphase.update(&self.circles);
+        if self.params.merge_on_contact && self.merge_touching() {
+            self.broadphase.update(&self.circles);
+        }
+        let pairs = self.broadphase.pairs(&self.circles, self.params.threads);
+        self.contacts.extend(pairs.iter().map(|&(a, b)| Contact::between(&self.circles, a, b)));
+        // Piles an awake circle has run into wake now; what still sleeps only
+        // touches other sleepers or pins and stays put
+        self.wake_touched_islands();
+        let circles = &self.circles;
+        let pairs: Vec<(usize, usize)> = pairs.into_iter().filter(|&(a, b)| !circles[a].sleeping && !circles[b].sleeping).collect();
+        if self.params.threads > 1 {
+            self.resolve_collisions_in_batches(pairs);
+        } else {
+            let params = &self.params;
+            for (a, b) in pairs {
+                resolve_collision(&mut self.circles, a, b, params, &mut self.rng);
+            }
+        }
+
+        for body in &self.shapebodies {
+            if !asleep(&self.circles, &body.nodes) {
+                body.apply_shape_matching(&mut self.circles, dt);
+            }
+        }
+
+        self.break_links();
+        // The link solver only moves circles; how far it moved them becomes
+        // velocity afterwards, so a chain held still ends the step still
+        let before: Vec<Double> = self.circles.iter().map(|circle| circle.pinfo.pos).collect();
+        if self.params.threads > 1 {
+            self.solve_links_in_batches();
+        } else {
+            for _ in 0..self.params.link_iterations {
+                for slink in &self.staticlinks {
+                    if !asleep(&self.circles, &[slink.c1, slink.c2]) {
+                        apply_static_link(&mut self.circles, slink);
+                    }
+                }
+                for alink in &self.anglelinks {
+                    if !asleep(&self.circles, &[alink.c1, alink.c2, alink.c3]) {
+                        apply_angle_link(&mut self.circles, alink);
+                    }
+                }
+            }
+        }
+
+        for (circle, before) in self.circles.iter_mut().zip(before) {
+            circle.pinfo.vel = circle.pinfo.vel + (circle.pinfo.pos - before) * (1.0 / dt);
+        }
+    }
+
+    /// Resolves `pairs` in batches of pairs that share no circle, spread over
+    /// the worker threads. Coincident pairs jitter apart with a generator
+    /// seeded from the pair itself, so the thread that gets them makes no difference.
+    fn resolve_collisions_in_batches(&mut self, pairs: Vec<(usize, usize)>) {
+        let batches = color_batches(pairs, self.circles.len(), |&(a, b)| vec![a, b]);
+        let jitter_seed: u64 = self.rng.gen();
+        let params = &self.params;
+        solve_batches(&mut self.circles, &batches, params.threads, |circles, &(a, b), updates| {
+            let mut pair = [circles[a], circles[b]];
+            if coincident(&pair, 0, 1) {
+                let mut rng = ChaCha8Rng::seed_from_u64(jitter_seed ^ ((a as u64) << 32 | b as u64));
+                resolve_collision(&mut pair, 0, 1, params, &mut rng);
+            } else {
+                resolve_contact(&mut pair, 0, 1, params);
+            }
+            updates.push((a, pair[0].pinfo));
+            updates.push((b, pair[1].pinfo));
+        });
+    }
+
+    /// Runs the link solver passes with the static and angle links each split
+    /// into batches that share no circle, spread over the worker threads.
+    fn solve_links_in_batches(&mut self) {
+        let num = self.circles.len();
+        let circles = &self.circles;
+        let statics: Vec<StaticLink> = self.staticlinks.iter().filter(|slink| !asleep(circles, &[slink.c1, slink.c2])).copied().collect();
+        let angles: Vec<AngleLink> = self.anglelinks.iter().filter(|alink| !asleep(circles, &[alink.c1, alink.c2, alink.c3])).copied().collect();
+        let statics = color_batches(statics, num, |slink| vec![slink.c1, slink.c2]);
+        let angles = color_batches(angles, num, |alink| vec![alink.c1, alink.c2, alink.c3]);
+        let statics = statics.into_iter().map(|batch| batch.into_iter().map(Constraint::Static).collect::<Vec<_>>());
+        let angles = angles.into_iter().map(|batch| batch.into_iter().map(Constraint::Angle).collect::<Vec<_>>());
+        let pass: Vec<Vec<Constraint>> = statics.into_iter().chain(angles).collect();
+        let schedule: Vec<Vec<Constraint>> = (0..self.params.link_iterations).flat_map(|_| pass.iter().cloned()).collect();
+
+        solve_batches(&mut self.circles, &schedule, self.params.threads, |circles, constraint, updates| match *constraint {
+            Constraint::Static(slink) => {
+                let mut pair = [circles[slink.c1], circles[slink.c2]];
+                apply_static_link(&mut pair, &StaticLink { c1: 0, c2: 1, ..slink });
+                updates.push((slink.c1, pair[0].pinfo));
+                updates.push((slink.c2, pair[1].pinfo));
+            }
+            Constraint::Angle(alink) => {
+                let mut triple = [circles[alink.c1], circles[alink.c2], circles[alink.c3]];
+                apply_angle_link(&mut triple, &AngleLink { c1: 0, c2: 1, c3: 2, ..alink });
+                for (id, circle) in [alink.c1, alink.c2, alink.c3].into_iter().zip(triple) {
+                    updates.push((id, circle.pinfo));
+                }
+            }
+        });
+    }
+
+    /// Measures the strain on every spring and link and removes those
+    /// stretched past their limit, recording them for [`World::take_broken_links`].
+    /// Angle links bending across a snapped link go with it.
+    fn break_links(&mut self) {
+        let circles = &self.circles;
+        let already_broken = self.broken.len();
+        let broken = &mut self.broken;
+        self.links.retain_mut(|link| {
+            link.strain = strain(circles, link.c1, link.c2, link.rest_length);
+            let snapped = link.max_strain.is_some_and(|max| link.strain > max);
+            if snapped {
+                broken.push(BrokenLink::Spring(*link));
+            }
+            !snapped
+        });
+        self.staticlinks.retain_mut(|slink| {
+            slink.strain = strain(circles, slink.c1, slink.c2, slink.rest_length);
+            let snapped = slink.max_strain.is_some_and(|max| slink.strain > max);
+            if snapped {
+                broken.push(BrokenLink::Static(*slink));
+            }
+            !snapped
+        });
+
+        for snapped in &self.broken[already_broken..] {
+            let (c1, c2) = match snapped {
+                BrokenLink::Spring(link) => (link.c1, link.c2),
+                BrokenLink::Static(slink) => (slink.c1, slink.c2),
+            };
+            let arm = |a: usize, b: usize| (a == c1 && b == c2) || (a == c2 && b == c1);
+            self.anglelinks.retain(|alink| !arm(alink.c1, alink.c2) && !arm(alink.c2, alink.c3));
+        }
+    }
+
+    /// Springs and links that snapped since the last call, oldest first.
+    pub fn take_broken_links(&mut self) -> Vec<BrokenLink> {
+        std::mem::take(&mut self.broken)
+    }
+
+    /// Fuses touching pairs of free circles, returning whether any merged.
+    /// Circles that are pinned, dragged, part of a link or a shape body never merge.
+    fn merge_touching(&mut self) -> bool {
+        let mut locked: Vec<bool> = self.circles.iter().map(|circle| circle.pinned || circle.is_dragged).collect();
+        for link in &self.links {
+            locked[link.c1] = true;
+            locked[link.c2] = true;
+        }
+        for slink in &self.staticlinks {
+            locked[slink.c1] = true;
+            locked[slink.c2] = true;
+        }
+        for body in &self.shapebodies {
+            for &node in &body.nodes {
+                locked[node] = true;
+            }
+        }
+
+        let mut removed = vec![false; self.circles.len()];
+        for (a, b) in self.broadphase.pairs(&self.circles, self.params.threads) {
+            let (keep, gone) = (a.min(b), a.max(b));
+            if locked[keep] || locked[gone] || removed[keep] || removed[gone] {
+                continue;
+            }
+            let other = self.circles[gone];
+            self.circles[keep].absorb(&other);
+            removed[gone] = true;
+        }
+
+        let mut merged = false;
+        for id in (0..removed.len()).rev() {
+            if removed[id] {
+                self.remove_circle(id);
+                merged = true;
+            }
+        }
+        merged
+    }
+
+    /// Moves every circle forward with the selected integrator.
+    fn integrate(&mut self, dt: f64) {
+        for circle in &mut self.circles {
+            if circle.is_dragged {
+                circle.pinfo.vel = (self.mouse_pos - circle.pinfo.pos) * (1.0 / dt);
+            } else if circle.pinned {
+                circle.pinfo.vel = Double { x: 0.0, y: 0.0 };
+                circle.pinfo.ang_vel = 0.0;
+            }
+        }
+
+        let mut pos: Vec<Double> = self.circles.iter().map(|circle| circle.pinfo.pos).collect();
+        let mut vel: Vec<Double> = self.circles.iter().map(|circle| circle.pinfo.vel).collect();
+        let moving: Vec<bool> = self.circles.iter().map(|circle| !circle.sleeping).collect();
+        let acc = self.params.integrator.integrate(&mut pos, &mut vel, dt, &moving, self.params.threads, |pos, vel| self.accelerations(pos, vel));
+
+        let spin_damping = 1.0 - self.params.air_resistance * dt;
+        for (i, circle) in self.circles.iter_mut().enumerate().filter(|(_, circle)| !circle.sleeping) {
+            circle.pinfo.pos = pos[i];
+            circle.pinfo.vel = vel[i];
+            circle.pinfo.acc = acc[i];
+            circle.pinfo.ang_vel *= spin_damping;
+            circle.pinfo.angle += circle.pinfo.ang_vel * dt;
+        }
+    }
+
+    pub fn broadphase(&self) -> &dyn BroadPhase {
+        self.broadphase.as_ref()
+    }
+
+    /// Swaps the structure used to find colliding pairs, returning the old one.
+    pub fn set_broadphase(&mut self, broadphase: Box<dyn BroadPhase>) -> Box<dyn BroadPhase> {
+        std::mem::replace(&mut self.broadphase, broadphase)
+    }
+
+    pub fn params(&self) -> &Parameters {
+        &self.params
+    }
+
+    /// Mutable access to the parameters. Any change may disturb a resting
+    /// pile, so every circle is woken.
+    pub fn params_mut(&mut self) -> &mut Parameters {
+        self.wake_all();
+        &mut self.params
+    }
+
+    /// Replaces the parameter set, returning the previous one.
+    pub fn set_params(&mut self, params: Parameters) -> Parameters {
+        self.wake_all();
+        std::mem::replace(&mut self.params, params)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Restarts the random sequence from `seed`.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    /// Remembers current positions as the start of the next fixed step.
+    pub fn store_previous_positions(&mut self) {
+        for circle in &mut self.circles {
+            circle.pinfo.prev_pos = circle.pinfo.pos;
+        }
+    }
+
+    pub fn circles(&self) -> &[Circle] {
+        &self.circles
+    }
+
+    pub fn circle(&self, id: usize) -> Option<&Circle> {
+        self.circles.get(id)
+    }
+
+    pub fn circle_mut(&mut self, id: usize) -> Option<&mut Circle> {
+        self.circles.get_mut(id)
+    }
+
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    pub fn static_links(&self) -> &[StaticLink] {
+        &self.staticlinks
+    }
+
+    pub fn angle_links(&self) -> &[AngleLink] {
+        &self.anglelinks
+    }
+
+    pub fn pressure_bodies(&self) -> &[PressureBody] {
+        &self.pressurebodies
+    }
+
+    pub fn shape_bodies(&self) -> &[ShapeBody] {
+        &self.shapebodies
+    }
+
+    /// Index of the first circle containing `point`, if any.
+    pub fn circle_at(&self, point: Double) -> Option<usize> {
+        self.circles.iter().position(|circle| circle.contains(point))
+    }
+
+    pub fn add_circle(&mut self, circle: Circle) -> usize {
+        self.circles.push(circle);
+        self.circles.len() - 1
+    }
+
+    pub fn add_random_circles(&mut self, count: usize) {
+        for _ in 0..count {
+            let mut circle = Circle::new(
+                Double {
+                    x: WIDTH as f64 * self.rng.gen::<f64>(),
+                    y: HEIGHT as f64 * self.rng.gen::<f64>(),
+                },
+                self.rng.gen::<f64>() * (RADIUS_MAX - RADIUS_MIN) + RADIUS_MIN,
+                [self.rng.gen::<f32>(), self.rng.gen::<f32>(), self.rng.gen::<f32>(), 1.0],
+            );
+            circle.pinfo.vel = Double { x: self.rng.gen::<f64>() * 50.0 - 25.0, y: self.rng.gen::<f64>() * 50.0 - 25.0 };
+            self.circles.push(circle.with_density(self.params.density));
+        }
+    }
+
+    /// Removes a circle together with every link attached to it.
+    pub fn remove_circle(&mut self, id: usize) -> Option<Circle> {
+        if id >= self.circles.len() {
+            return None;
+        }
+        let circle = self.circles.remove(id);
+        // Whatever rested on it has lost its support
+        self.wake_all();
+        let shift = |c: usize| if c > id { c - 1 } else { c };
+
+        self.links.retain(|link| link.c1 != id && link.c2 != id);
+        for link in &mut self.links {
+            link.c1 = shift(link.c1);
+            link.c2 = shift(link.c2);
+        }
+        self.staticlinks.retain(|slink| slink.c1 != id && slink.c2 != id);
+        for slink in &mut self.staticlinks {
+            slink.c1 = shift(slink.c1);
+            slink.c2 = shift(slink.c2);
+        }
+        self.anglelinks.retain(|alink| alink.c1 != id && alink.c2 != id && alink.c3 != id);
+        for alink in &mut self.anglelinks {
+            alink.c1 = shift(alink.c1);
+            alink.c2 = shift(alink.c2);
+            alink.c3 = shift(alink.c3);
+        }
+        self.pressurebodies.retain(|body| !body.nodes.contains(&id));
+        for body in &mut self.pressurebodies {
+            for node in &mut body.nodes {
+                *node = shift(*node);
+            }
+        }
+        self.shapebodies.retain(|body| !body.nodes.contains(&id));
+        for body in &mut self.shapebodies {
+            for node in &mut body.nodes {
+                *node = shift(*node);
+            }
+        }
+        Some(circle)
+    }
+
+    pub fn add_link(&mut self, link: Link) -> usize {
+        self.links.push(link);
+        self.links.len() - 1
+    }
+
+    pub fn remove_link(&mut self, id: usize) -> Option<Link> {
+        (id < self.links.len()).then(|| self.links.remove(id))
+    }
+
+    pub fn add_static_link(&mut self, slink: StaticLink) -> usize {
+        self.staticlinks.push(slink);
+        self.staticlinks.len() - 1
+    }
+
+    pub fn remove_static_link(&mut self, id: usize) -> Option<StaticLink> {
+        (id < self.staticlinks.len()).then(|| self.staticlinks.remove(id))
+    }
+
+    pub fn add_angle_link(&mut self, alink: AngleLink) -> usize {
+        self.anglelinks.push(alink);
+        self.anglelinks.len() - 1
+    }
+
+    pub fn remove_angle_link(&mut self, id: usize) -> Option<AngleLink> {
+        (id < self.anglelinks.len()).then(|| self.anglelinks.remove(id))
+    }
+
+    pub fn zones(&self) -> &[Zone] {
+        &self.zones
+    }
+
+    pub fn add_zone(&mut self, zone: Zone) -> usize {
+        self.wake_all();
+        self.zones.push(zone);
+        self.zones.len() - 1
+    }
+
+    pub fn remove_zone(&mut self, id: usize) -> Option<Zone> {
+        self.wake_all();
+        (id < self.zones.len()).then(|| self.zones.remove(id))
+    }
+
+    pub fn add_rope(&mut self, anchor_pos: Double, rope_length: f64, segment_num: i64, pin_first: bool) {
+        let first = self.circles.len();
+        let first_static = self.staticlinks.len();
+        create_rope(&mut self.circles, &mut self.staticlinks, anchor_pos, rope_length, segment_num, pin_first);
+        self.apply_density(first);
+        self.apply_group(first);
+        self.apply_break_strain(self.links.len(), first_static);
+        self.apply_bending(first, false);
+    }
+
+    pub fn add_cloth(&mut self, top_left: Double, width: usize, height: usize, spacing: f64, pin_top: bool) {
+        let first = self.circles.len();
+        let first_static = self.staticlinks.len();
+        create_cloth(&mut self.circles, &mut self.staticlinks, top_left, width, height, spacing, pin_top);
+        self.apply_density(first);
+        self.apply_group(first);
+        self.apply_break_strain(self.links.len(), first_static);
+    }
+
+    pub fn add_softbody(&mut self, num_of_circles: usize, radius: f64, sub_radius: f64, pos: Double) {
+        let first = self.circles.len();
+        let first_static = self.staticlinks.len();
+        create_softbody(&mut self.circles, &mut self.staticlinks, num_of_circles, radius, sub_radius, pos, self.params.default_color);
+        self.apply_density(first);
+        self.apply_group(first);
+        self.apply_break_strain(self.links.len(), first_static);
+        self.apply_bending(first, true);
+    }
+
+    pub fn add_spring_softbody(&mut self, num_of_circles: usize, radius: f64, sub_radius: f64, pos: Double) {
+        let first = self.circles.len();
+        let first_link = self.links.len();
+        create_spring_softbody(&mut self.circles, &mut self.links, num_of_circles, radius, sub_radius, pos, self.params.default_color);
+        self.apply_density(first);
+        self.apply_group(first);
+        self.apply_break_strain(first_link, self.staticlinks.len());
+        self.apply_bending(first, true);
+    }
+
+    /// A softbody ring whose interior holds `gas_amount` of gas, so it keeps its
+    /// volume and bounces like a balloon.
+    pub fn add_pressure_body(&mut self, num_of_circles: usize, radius: f64, sub_radius: f64, pos: Double, gas_amount: f64) {
+        let first = self.circles.len();
+        self.add_softbody(num_of_circles, radius, sub_radius, pos);
+        self.pressurebodies.push(PressureBody::new((first..self.circles.len()).collect(), gas_amount));
+    }
+
+    /// A ring of unlinked circles held in shape by shape matching with the given `stiffness`.
+    pub fn add_shape_softbody(&mut self, num_of_circles: usize, radius: f64, sub_radius: f64, pos: Double, stiffness: f64) {
+        let first = self.circles.len();
+        create_ring(&mut self.circles, num_of_circles, radius, sub_radius, pos, self.params.default_color);
+        self.apply_density(first);
+        self.apply_group(first);
+        let body = ShapeBody::from_current(&self.circles, (first..self.circles.len()).collect(), stiffness);
+        self.shapebodies.push(body);
+    }
+
+    /// Adds a shape-matching body over existing circles, such as one built
+    /// with [`ShapeBody::from_current`] to remember an arbitrary outline.
+    pub fn add_shape_body(&mut self, body: ShapeBody) -> usize {
+        self.shapebodies.push(body);
+        self.shapebodies.len() - 1
+    }
+
+    /// A single SPH fluid particle of the world's fluid radius.
+    pub fn add_fluid_particle(&mut self, pos: Double) -> usize {
+        let circle = Circle::new(pos, self.params.fluid_radius, FLUID_COLOR)
+            .with_density(self.params.density)
+            .with_fluid(true);
+        self.add_circle(circle)
+    }
+
+    /// A `width` × `height` block of fluid particles packed edge to edge from `top_left`.
+    pub fn add_fluid_block(&mut self, top_left: Double, width: usize, height: usize) {
+        let spacing = 2.0 * self.params.fluid_radius;
+        for y in 0..height {
+            for x in 0..width {
+                self.add_fluid_particle(top_left + Double { x: x as f64 * spacing, y: y as f64 * spacing });
+            }
+        }
+    }
+
+    /// Fills the disc of `radius` around `center` with fluid particles, packed
+    /// edge to edge, skipping spots that already hold a fluid particle.
+    pub fn fill_fluid_disc(&mut self, center: Double, radius: f64) {
+        let spacing = 2.0 * self.params.fluid_radius;
+        if spacing <= 0.0 {
+            return;
+        }
+        let steps = (radius / spacing).floor() as i64;
+        for y in -steps..=steps {
+            for x in -steps..=steps {
+                let pos = center + Double { x: x as f64 * spacing, y: y as f64 * spacing };
+                let free = self.circles.iter().all(|circle| !circle.fluid || circle.pinfo.pos.dist(pos) >= spacing * 0.9);
+                if pos.dist(center) <= radius && free {
+                    self.add_fluid_particle(pos);
+                }
+            }
+        }
+    }
+
+    /// SPH density at every circle, zero for circles that are not fluid.
+    pub fn fluid_densities(&self) -> Vec<f64> {
+        let pos: Vec<Double> = self.circles.iter().map(|circle| circle.pinfo.pos).collect();
+        let mass: Vec<f64> = self.circles.iter().map(|circle| circle.mass).collect();
+        let fluid: Vec<usize> = (0..self.circles.len()).filter(|id| self.circles[*id].fluid).collect();
+        densities(&pos, &mass, &fluid, &self.params)
+    }
+
+    /// Gives generated circles from index `first` onwards the world's default density.
+    fn apply_density(&mut self, first: usize) {
+        let density = self.params.density;
+        for circle in &mut self.circles[first..] {
+            circle.mass = circle.area() * density;
+        }
+    }
+
+    /// Stiffens the chain of generated circles from index `first` onwards
+    /// against bending when the world's bend stiffness is set, joining the
+    /// ends as well when the chain is a `closed` ring.
+    fn apply_bending(&mut self, first: usize, closed: bool) {
+        if self.params.bend_stiffness > 0.0 {
+            create_bending(&self.circles, &mut self.anglelinks, first, closed, self.params.bend_stiffness);
+        }
+    }
+
+    /// Puts generated circles from index `first` onwards in a fresh collision
+    /// group, so the nodes of one body do not jam against each other.
+    fn apply_group(&mut self, first: usize) {
+        let group = self.new_group();
+        for circle in &mut self.circles[first..] {
+            circle.group = group;
+        }
+    }
+
+    /// A collision group no circle has been given yet.
+    pub fn new_group(&mut self) -> u32 {
+        let group = self.next_group;
+        self.next_group = self.next_group.wrapping_add(1).max(1);
+        group
+    }
+
+    /// Gives generated springs and links from the given indices onwards the world's break strain.
+    fn apply_break_strain(&mut self, first_link: usize, first_static: usize) {
+        let max_strain = self.params.break_limit();
+        for link in &mut self.links[first_link..] {
+            link.max_strain = max_strain;
+        }
+        for slink in &mut self.staticlinks[first_static..] {
+            slink.max_strain = max_strain;
+        }
+    }
+
+    /// Pins or unpins a circle, returning `false` if it does not exist.
+    pub fn set_pinned(&mut self, id: usize, pinned: bool) -> bool {
+        match self.circles.get_mut(id) {
+            Some(circle) => {
+                circle.pinned = pinned;
+                circle.wake();
+                circle.pinfo.vel = Double { x: 0.0, y: 0.0 };
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Where dragged circles are pulled towards on the next step.
+    pub fn set_mouse_pos(&mut self, pos: Double) {
+        self.mouse_pos = pos;
+    }
+
+    /// Starts dragging the circle under `point`, returning its index.
+    pub fn grab(&mut self, point: Double) -> Option<usize> {
+        let id = self.circle_at(point)?;
+        self.circles[id].is_dragged = true;
+        self.circles[id].wake();
+        Some(id)
+    }
+
+    pub fn release(&mut self) {
+        for circle in &mut self.circles {
+            circle.is_dragged = false;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.circles.clear();
+        self.links.clear();
+        self.staticlinks.clear();
+        self.anglelinks.clear();
+        self.pressurebodies.clear();
+        self.shapebodies.clear();
+        self.zones.clear();
+        self.broken.clear();
+        self.contacts.clear();
+        self.next_group = 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(world: &World) -> Vec<(u64, u64)> {
+        world.circles().iter().map(|circle| (circle.pinfo.pos.x.to_bits(), circle.pinfo.pos.y.to_bits())).collect()
+    }
+
+    #[test]
+    fn same_seed_runs_are_identical() {
+        let run = |seed| {
+            let mut world = World::with_seed(seed);
+            world.add_random_circles(40);
+            for _ in 0..300 {
+                world.step(1.0 / 60.0);
+            }
+            positions(&world)
+        };
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(43));
+    }
+
+    #[test]
+    fn collisions_conserve_momentum() {
+        let mut world = World::with_seed(1);
+        let params = world.params_mut();
+        params.gravity = Double { x: 0.0, y: 0.0 };
+        params.air_resistance = 0.0;
+        let light = world.add_circle(Circle::new(Double { x: 300.0, y: 300.0 }, 10.0, [1.0; 4]).with_density(1.0));
+        let heavy = world.add_circle(Circle::new(Double { x: 500.0, y: 310.0 }, 20.0, [1.0; 4]).with_density(1.0));
+        world.circle_mut(light).unwrap().pinfo.vel = Double { x: 40.0, y: 5.0 };
+        world.circle_mut(heavy).unwrap().pinfo.vel = Double { x: -30.0, y: 0.0 };
+        world.circle_mut(heavy).unwrap().pinfo.ang_vel = 1.0;
+
+        let momentum = |world: &World| {
+            world
+                .circles()
+                .iter()
+                .fold(Double { x: 0.0, y: 0.0 }, |sum, circle| sum + circle.pinfo.vel * circle.mass)
+        };
+        let before = momentum(&world);
+        let mut touched = false;
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+            touched |= !world.contacts().is_empty();
+        }
+        let after = momentum(&world);
+
+        assert!(touched);
+        assert!(before.dist(after) < 1e-6 * before.magnitude(), "{:?} became {:?}", before, after);
+    }
+
+    #[test]
+    fn coincident_circles_leave_pinned_ones_in_place() {
+        for threads in [1, 2] {
+            let mut world = World::with_seed(3);
+            world.params_mut().threads = threads;
+            let centre = Double { x: 400.0, y: 300.0 };
+            let pinned = world.add_circle(Circle::new(centre, 10.0, [1.0; 4]).with_pinned(true));
+            let free = world.add_circle(Circle::new(centre, 10.0, [1.0; 4]));
+            for _ in 0..10 {
+                world.step(1.0 / 60.0);
+            }
+            assert_eq!(world.circles()[pinned].pinfo.pos, centre);
+            assert!(world.circles()[free].pinfo.pos.dist(centre) >= 19.0);
+        }
+    }
+
+    #[test]
+    fn fast_small_circles_do_not_tunnel() {
+        let mut world = World::with_seed(5);
+        let params = world.params_mut();
+        params.gravity = Double { x: 0.0, y: 0.0 };
+        params.air_resistance = 0.0;
+        let left = world.add_circle(Circle::new(Double { x: 300.0, y: 300.0 }, 1.0, [1.0; 4]));
+        let right = world.add_circle(Circle::new(Double { x: 500.0, y: 300.0 }, 1.0, [1.0; 4]));
+        world.circle_mut(left).unwrap().pinfo.vel = Double { x: 3000.0, y: 0.0 };
+        world.circle_mut(right).unwrap().pinfo.vel = Double { x: -3000.0, y: 0.0 };
+        for _ in 0..5 {
+            world.step(1.0 / 60.0);
+        }
+        assert!(world.circles()[left].pinfo.pos.x < world.circles()[right].pinfo.pos.x);
+    }
+
+    #[test]
+    fn sleeping_circles_take_their_share_of_a_collision() {
+        let mut world = World::with_seed(7);
+        let params = world.params_mut();
+        params.gravity = Double { x: 0.0, y: 0.0 };
+        let resting = world.add_circle(Circle::new(Double { x: 400.0, y: 400.0 }, 20.0, [1.0; 4]));
+        for _ in 0..120 {
+            world.step(1.0 / 60.0);
+        }
+        assert!(world.circles()[resting].sleeping);
+
+        let ball = world.add_circle(Circle::new(Double { x: 400.0, y: 300.0 }, 20.0, [1.0; 4]));
+        world.circle_mut(ball).unwrap().pinfo.vel = Double { x: 0.0, y: 300.0 };
+        for _ in 0..15 {
+            world.step(1.0 / 60.0);
+        }
+        assert!(!world.circles()[resting].sleeping);
+        assert!(world.circles()[resting].pinfo.vel.y > 150.0);
+        assert!(world.circles()[ball].pinfo.vel.y < world.circles()[resting].pinfo.vel.y);
+    }
+
+    #[test]
+    fn dragging_a_pinned_circle_wakes_what_rests_on_it() {
+        let mut world = World::with_seed(9);
+        let pin = world.add_circle(Circle::new(Double { x: 400.0, y: 400.0 }, 20.0, [1.0; 4]).with_pinned(true));
+        let ball = world.add_circle(Circle::new(Double { x: 400.0, y: 360.0 }, 20.0, [1.0; 4]));
+        for _ in 0..180 {
+            world.step(1.0 / 60.0);
+        }
+        assert!(world.circles()[ball].sleeping);
+
+        assert_eq!(world.grab(Double { x: 400.0, y: 400.0 }), Some(pin));
+        for frame in 1..=30 {
+            world.set_mouse_pos(Double { x: 400.0, y: 400.0 - 4.0 * frame as f64 });
+            world.step(1.0 / 60.0);
+        }
+        assert!(!world.circles()[ball].sleeping);
+        assert!(world.circles()[ball].pinfo.pos.y < world.circles()[pin].pinfo.pos.y - 30.0);
+    }
+
+    #[test]
+    fn merging_leaves_shape_bodies_whole() {
+        let mut world = World::with_seed(13);
+        let params = world.params_mut();
+        params.gravity = Double { x: 0.0, y: 0.0 };
+        params.merge_on_contact = true;
+        world.add_shape_softbody(12, 40.0, 6.0, Double { x: 400.0, y: 300.0 }, 0.5);
+        let ball = world.add_circle(Circle::new(Double { x: 300.0, y: 300.0 }, 10.0, [1.0; 4]));
+        world.circle_mut(ball).unwrap().pinfo.vel = Double { x: 200.0, y: 0.0 };
+        let mut touched = false;
+        for _ in 0..30 {
+            world.step(1.0 / 60.0);
+            touched |= !world.contacts().is_empty();
+        }
+        assert_eq!(world.shape_bodies().len(), 1);
+        assert!(touched);
+    }
+
+    #[test]
+    fn hanging_ropes_come_to_rest() {
+        let mut world = World::with_seed(17);
+        world.params_mut().sleep = false;
+        world.add_rope(Double { x: 400.0, y: 100.0 }, 200.0, 10, true);
+        for _ in 0..600 {
+            world.step(1.0 / 60.0);
+        }
+        let tip = world.circles().len() - 1;
+        let before = world.circles()[tip].pinfo.pos;
+        for _ in 0..100 {
+            world.step(1.0 / 60.0);
+        }
+        let circle = &world.circles()[tip];
+        assert!(circle.pinfo.pos.dist(before) < 0.01);
+        assert!(circle.pinfo.vel.magnitude() < 0.1, "{:?}", circle.pinfo.vel);
+    }
+}
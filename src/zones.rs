@@ -0,0 +1,86 @@
+use crate::math::Double;
+
+/// Region of the world a [`Zone`] acts on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ZoneShape {
+    Rect { min: Double, max: Double },
+    Circle { center: Double, radius: f64 },
+}
+
+impl ZoneShape {
+    /// An axis-aligned rectangle of `width` × `height` centred on `center`.
+    pub fn rect(center: Double, width: f64, height: f64) -> ZoneShape {
+        let half = Double { x: width.abs() / 2.0, y: height.abs() / 2.0 };
+        ZoneShape::Rect { min: center - half, max: center + half }
+    }
+
+    pub fn contains(&self, point: Double) -> bool {
+        match *self {
+            ZoneShape::Rect { min, max } => point.x >= min.x && point.x <= max.x && point.y >= min.y && point.y <= max.y,
+            ZoneShape::Circle { center, radius } => center.dist(point) <= radius,
+        }
+    }
+
+    pub fn center(&self) -> Double {
+        match *self {
+            ZoneShape::Rect { min, max } => (min + max) * 0.5,
+            ZoneShape::Circle { center, .. } => center,
+        }
+    }
+}
+
+/// What a [`Zone`] does to the circles inside it. Like gravity, every effect
+/// is an acceleration, so light and heavy circles respond alike.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ZoneKind {
+    /// Constant push in one direction.
+    Wind(Double),
+    /// Swirl around the zone's centre; positive turns clockwise on screen.
+    Vortex(f64),
+    /// Push away from the zone's centre, or pull towards it when negative.
+    Radial(f64),
+    /// Extra air resistance proportional to velocity.
+    Drag(f64),
+}
+
+impl ZoneKind {
+    /// Name used by the terminal commands that create this kind of zone.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ZoneKind::Wind(_) => "wind",
+            ZoneKind::Vortex(_) => "vortex",
+            ZoneKind::Radial(_) => "radial",
+            ZoneKind::Drag(_) => "drag",
+        }
+    }
+}
+
+/// A force field confined to a region.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Zone {
+    pub shape: ZoneShape,
+    pub kind: ZoneKind,
+}
+
+impl Zone {
+    pub fn new(shape: ZoneShape, kind: ZoneKind) -> Zone {
+        Zone { shape, kind }
+    }
+
+    /// Acceleration felt by a circle at `pos` moving at `vel`; zero outside the zone.
+    pub fn acceleration(&self, pos: Double, vel: Double) -> Double {
+        let zero = Double { x: 0.0, y: 0.0 };
+        if !self.shape.contains(pos) {
+            return zero;
+        }
+        let offset = pos - self.shape.center();
+        let distance = offset.magnitude();
+        match self.kind {
+            ZoneKind::Wind(force) => force,
+            ZoneKind::Vortex(strength) if distance > 0.0 => offset.perp() * (strength / distance),
+            ZoneKind::Radial(strength) if distance > 0.0 => offset * (strength / distance),
+            ZoneKind::Drag(coefficient) => vel * -coefficient,
+            _ => zero,
+        }
+    }
+}